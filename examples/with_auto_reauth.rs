@@ -27,7 +27,7 @@ async fn auth_code_do_things(spotify: &AuthCodeSpotify) {
 
     // Printing the followed artists
     let followed = spotify
-        .current_user_followed_artists(None, None)
+        .current_user_followed_artists_manual(None, None)
         .await
         .expect("couldn't get user followed artists");
     println!(
@@ -45,7 +45,7 @@ async fn auth_code_do_things(spotify: &AuthCodeSpotify) {
 async fn client_creds_do_things(spotify: &ClientCredsSpotify) {
     // Running the requests
     let birdy_uri = AlbumId::from_uri("spotify:album:0sNOF9WDwhWunNAHPD3Baj").unwrap();
-    let albums = spotify.album(birdy_uri).await;
+    let albums = spotify.album(birdy_uri, None).await;
     println!("Get albums: {}", albums.unwrap().id);
 }
 
@@ -34,7 +34,7 @@ async fn do_things(spotify: AuthCodeSpotify) {
 
     // Printing the followed artists
     let followed = spotify
-        .current_user_followed_artists(None, None)
+        .current_user_followed_artists_manual(None, None)
         .await
         .expect("couldn't get user followed artists");
     println!(
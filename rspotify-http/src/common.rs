@@ -1,5 +1,7 @@
 use std::collections::HashMap;
 use std::fmt;
+use std::sync::Arc;
+use std::time::Duration;
 
 use maybe_async::maybe_async;
 use serde_json::Value;
@@ -8,6 +10,180 @@ pub type Headers = HashMap<String, String>;
 pub type Query<'a> = HashMap<&'a str, &'a str>;
 pub type Form<'a> = HashMap<&'a str, &'a str>;
 
+/// Information about a request performed by the HTTP client, which is given
+/// to its [`RequestHook`].
+#[derive(Debug, Clone, Copy)]
+pub struct RequestInfo<'a> {
+    /// The HTTP method, like `GET`.
+    pub method: &'a str,
+    /// The requested URL. The query parameters aren't included, unless they
+    /// were already part of the URL given to the client.
+    pub url: &'a str,
+    /// The status code of the response, or `None` if the request couldn't be
+    /// completed (e.g. the connection failed or it timed out).
+    pub status: Option<u16>,
+    /// The time elapsed since the request was sent until its status code was
+    /// received, or it failed.
+    pub elapsed: Duration,
+}
+
+/// A callback invoked after every request performed by the HTTP client,
+/// including the retried ones, e.g. to collect metrics.
+#[derive(Clone)]
+pub struct RequestHook(Arc<dyn Fn(&RequestInfo<'_>) + Send + Sync>);
+
+impl RequestHook {
+    pub fn new<F>(hook: F) -> Self
+    where
+        F: Fn(&RequestInfo<'_>) + Send + Sync + 'static,
+    {
+        Self(Arc::new(hook))
+    }
+
+    pub fn call(&self, info: &RequestInfo<'_>) {
+        (self.0)(info)
+    }
+}
+
+impl fmt::Debug for RequestHook {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("RequestHook").finish_non_exhaustive()
+    }
+}
+
+/// The status codes of the server errors that are usually transient, after
+/// which the requests may be retried.
+const TRANSIENT_STATUS_CODES: [u16; 4] = [500, 502, 503, 504];
+
+/// The maximum time to wait before retrying a request, so that neither the
+/// delays that double after every attempt nor the ones in `Retry-After` grow
+/// indefinitely.
+const MAX_RETRY_DELAY: Duration = Duration::from_secs(60);
+
+/// How the HTTP clients retry the requests that failed.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct RetryPolicy {
+    /// The maximum number of times a rate limited request is retried.
+    pub max_retries: u32,
+    /// The time to wait before retrying a request when Spotify doesn't
+    /// specify it, which is doubled after every attempt.
+    pub retry_base_delay: Duration,
+    /// The maximum number of times a request is retried after a transient
+    /// server error.
+    pub max_server_error_retries: u32,
+    /// Whether the requests other than `GET` are retried after server errors
+    /// as well.
+    pub retry_non_idempotent: bool,
+    /// Whether to randomize the delays before retrying after server errors.
+    pub jitter: bool,
+}
+
+/// The number of times a request has been retried so far, for each reason.
+#[derive(Debug, Default)]
+pub(crate) struct Retries {
+    rate_limited: u32,
+    server_error: u32,
+}
+
+impl RetryPolicy {
+    /// Returns how long to wait before retrying a request that failed with
+    /// `status`, or `None` if it shouldn't be retried anymore. `retry_after`
+    /// is the value of the `Retry-After` header in the response, if any.
+    pub(crate) fn retry_delay(
+        &self,
+        method: &str,
+        status: u16,
+        retry_after: Option<&str>,
+        retries: &mut Retries,
+    ) -> Option<Duration> {
+        if status == 429 {
+            if retries.rate_limited >= self.max_retries {
+                return None;
+            }
+
+            // Just like the backoff, the delay Spotify asks for is capped
+            let retry_after = retry_after
+                .and_then(|value| value.parse().ok())
+                .map(|secs| Duration::from_secs(secs).min(MAX_RETRY_DELAY));
+            let delay = retry_after.unwrap_or_else(|| self.backoff(retries.rate_limited));
+            retries.rate_limited += 1;
+            log::warn!("Rate limited, retrying in {:?}", delay);
+            Some(delay)
+        } else if TRANSIENT_STATUS_CODES.contains(&status) {
+            let retryable = method == "GET" || self.retry_non_idempotent;
+            if !retryable || retries.server_error >= self.max_server_error_retries {
+                return None;
+            }
+
+            let mut delay = self.backoff(retries.server_error);
+            if self.jitter {
+                delay = jitter(delay);
+            }
+            retries.server_error += 1;
+            log::warn!("Server error {}, retrying in {:?}", status, delay);
+            Some(delay)
+        } else {
+            None
+        }
+    }
+
+    /// The delay before the given retry, without counting `Retry-After`. It's
+    /// at most [`MAX_RETRY_DELAY`].
+    fn backoff(&self, attempt: u32) -> Duration {
+        self.retry_base_delay
+            .checked_mul(2u32.saturating_pow(attempt))
+            .map_or(MAX_RETRY_DELAY, |delay| delay.min(MAX_RETRY_DELAY))
+    }
+}
+
+/// Randomizes a delay between its half and its full value, so that the
+/// clients that failed at the same time don't retry at the same time too.
+fn jitter(delay: Duration) -> Duration {
+    let mut buf = [0u8; 4];
+    if getrandom::getrandom(&mut buf).is_err() {
+        return delay;
+    }
+
+    let fraction = f64::from(u32::from_ne_bytes(buf)) / f64::from(u32::MAX);
+    delay.mul_f64(0.5 + fraction / 2.0)
+}
+
+/// The headers whose values are replaced with `***` in the logs, since they
+/// contain credentials like the access token.
+const SECRET_HEADERS: [&str; 2] = ["authorization", "proxy-authorization"];
+
+/// Logs the request about to be performed. The values of the headers in
+/// [`SECRET_HEADERS`] are redacted, unless `log_secrets` is set.
+pub(crate) fn log_request<'a>(
+    method: &str,
+    url: &str,
+    headers: impl Iterator<Item = (&'a str, &'a str)>,
+    log_secrets: bool,
+) {
+    if !log::log_enabled!(log::Level::Info) {
+        return;
+    }
+
+    let headers = headers
+        .map(|(key, val)| {
+            let secret = SECRET_HEADERS
+                .iter()
+                .any(|header| header.eq_ignore_ascii_case(key));
+            if secret && !log_secrets {
+                (key, "***")
+            } else {
+                (key, val)
+            }
+        })
+        .collect::<Vec<_>>();
+    log::info!(
+        "Making request {} {} with headers {:?}",
+        method,
+        url,
+        headers
+    );
+}
+
 /// This trait represents the interface to be implemented for an HTTP client,
 /// which is kept separate from the Spotify client for cleaner code. Thus, it
 /// also requires other basic traits that are needed for the Spotify client.
@@ -51,6 +227,15 @@ pub trait BaseHttpClient: Send + Default + Clone + fmt::Debug {
         payload: &Value,
     ) -> Result<String, Self::Error>;
 
+    // Unlike `put`, the body is sent as is, so its content type should be
+    // specified in the headers.
+    async fn put_raw(
+        &self,
+        url: &str,
+        headers: Option<&Headers>,
+        payload: &str,
+    ) -> Result<String, Self::Error>;
+
     async fn delete(
         &self,
         url: &str,
@@ -58,3 +243,50 @@ pub trait BaseHttpClient: Send + Default + Clone + fmt::Debug {
         payload: &Value,
     ) -> Result<String, Self::Error>;
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_backoff() {
+        let policy = RetryPolicy {
+            retry_base_delay: Duration::from_secs(1),
+            ..Default::default()
+        };
+        assert_eq!(policy.backoff(0), Duration::from_secs(1));
+        assert_eq!(policy.backoff(3), Duration::from_secs(8));
+        // The delay is capped instead of doubling indefinitely
+        assert_eq!(policy.backoff(20), MAX_RETRY_DELAY);
+        assert_eq!(policy.backoff(u32::MAX), MAX_RETRY_DELAY);
+
+        // Multiplying a huge base delay doesn't overflow
+        let policy = RetryPolicy {
+            retry_base_delay: Duration::MAX,
+            ..Default::default()
+        };
+        assert_eq!(policy.backoff(0), MAX_RETRY_DELAY);
+        assert_eq!(policy.backoff(1), MAX_RETRY_DELAY);
+    }
+
+    #[test]
+    fn test_rate_limit_delay() {
+        let policy = RetryPolicy {
+            max_retries: 3,
+            retry_base_delay: Duration::from_secs(40),
+            ..Default::default()
+        };
+        let mut retries = Retries::default();
+
+        let delay = policy.retry_delay("GET", 429, Some("5"), &mut retries);
+        assert_eq!(delay, Some(Duration::from_secs(5)));
+        // Both `Retry-After` and the backoff without it are capped
+        let delay = policy.retry_delay("GET", 429, Some("86400"), &mut retries);
+        assert_eq!(delay, Some(MAX_RETRY_DELAY));
+        let delay = policy.retry_delay("GET", 429, None, &mut retries);
+        assert_eq!(delay, Some(MAX_RETRY_DELAY));
+        // No more retries are left
+        let delay = policy.retry_delay("GET", 429, None, &mut retries);
+        assert_eq!(delay, None);
+    }
+}
@@ -9,6 +9,10 @@
 #[cfg(not(all(feature = "client-reqwest", feature = "client-ureq")))]
 mod reqwest;
 
+#[cfg(feature = "client-reqwest")]
+#[cfg(not(all(feature = "client-reqwest", feature = "client-ureq")))]
+mod timer;
+
 #[cfg(feature = "client-ureq")]
 #[cfg(not(all(feature = "client-reqwest", feature = "client-ureq")))]
 mod ureq;
@@ -25,9 +29,16 @@ pub use self::reqwest::{ReqwestClient as HttpClient, ReqwestError as HttpError};
 #[cfg(not(all(feature = "client-reqwest", feature = "client-ureq")))]
 pub use self::ureq::{UreqClient as HttpClient, UreqError as HttpError};
 
+/// Not part of the public API; only meant to be used by `rspotify`.
+#[cfg(feature = "client-reqwest")]
+#[cfg(not(all(feature = "client-reqwest", feature = "client-ureq")))]
+#[doc(hidden)]
+pub mod __private {
+    pub use crate::timer::sleep;
+}
 #[cfg(any(feature = "client-reqwest", feature = "client-ureq"))]
 #[cfg(not(all(feature = "client-reqwest", feature = "client-ureq")))]
-pub use common::{BaseHttpClient, Form, Headers, Query};
+pub use common::{BaseHttpClient, Form, Headers, Query, RequestHook, RequestInfo};
 
 #[cfg(all(feature = "client-reqwest", feature = "client-ureq"))]
 compile_error!(
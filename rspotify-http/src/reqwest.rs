@@ -1,12 +1,19 @@
 //! The client implementation for the reqwest HTTP client, which is async by
 //! default.
 
-use super::{BaseHttpClient, Form, Headers, Query};
+use super::{
+    common::{log_request, Retries, RetryPolicy},
+    timer::sleep,
+    BaseHttpClient, Form, Headers, Query, RequestHook, RequestInfo,
+};
 
-use std::convert::TryInto;
+use std::{
+    convert::TryInto,
+    time::{Duration, Instant},
+};
 
 use maybe_async::async_impl;
-use reqwest::{Method, RequestBuilder};
+use reqwest::{header, Method, RequestBuilder};
 use serde_json::Value;
 
 /// Custom enum that contains all the possible errors that may occur when using
@@ -26,8 +33,11 @@ use serde_json::Value;
 ///     Err(HttpError::Client(e)) => eprintln!("request failed: {}", e),
 ///     Err(HttpError::StatusCode(response)) => {
 ///         let code = response.status().as_u16();
-///         match response.json::<rspotify_model::ApiError>().await {
-///             Ok(api_error) => eprintln!("status code {}: {:?}", code, api_error),
+///         match response.text().await {
+///             Ok(body) => {
+///                 let api_error = rspotify_model::ApiError::from_response(code, &body);
+///                 eprintln!("status code {}: {:?}", code, api_error)
+///             }
 ///             Err(_) => eprintln!("status code {}", code),
 ///         }
 ///     },
@@ -44,18 +54,148 @@ pub enum ReqwestError {
     /// The request was made, but the server returned an unsuccessful status
     /// code, such as 404 or 503. In some cases, the response may contain a
     /// custom message from Spotify with more information, which can be
-    /// serialized into `rspotify_model::ApiError`.
+    /// parsed with `rspotify_model::ApiError::from_response`.
     #[error("status code {}", reqwest::Response::status(.0))]
     StatusCode(reqwest::Response),
 }
 
+impl ReqwestError {
+    /// The status code of the response, in case the request was made but it
+    /// was unsuccessful.
+    #[must_use]
+    pub fn status(&self) -> Option<u16> {
+        match self {
+            Self::StatusCode(response) => Some(response.status().as_u16()),
+            Self::Client(err) => err.status().map(|status| status.as_u16()),
+        }
+    }
+
+    /// Whether the request failed because it took longer than the configured
+    /// timeout.
+    #[must_use]
+    pub fn is_timeout(&self) -> bool {
+        match self {
+            Self::Client(err) => err.is_timeout(),
+            Self::StatusCode(_) => false,
+        }
+    }
+}
+
 #[derive(Default, Debug, Clone)]
 pub struct ReqwestClient {
     /// reqwest needs an instance of its client to perform requests.
     client: reqwest::Client,
+    /// How the failed requests are retried.
+    retry: RetryPolicy,
+    /// The maximum time a single request may take.
+    timeout: Option<Duration>,
+    /// The callback invoked after every request.
+    request_hook: Option<RequestHook>,
+    /// The headers included in every request.
+    default_headers: header::HeaderMap,
+    /// Whether to log the credentials sent in the requests.
+    log_secrets: bool,
+}
+
+/// Uses an existing `reqwest::Client`, e.g. to share its connection pool or
+/// to customize its configuration.
+impl From<reqwest::Client> for ReqwestClient {
+    fn from(client: reqwest::Client) -> Self {
+        Self {
+            client,
+            ..Default::default()
+        }
+    }
 }
 
 impl ReqwestClient {
+    /// Retries the requests that were rate limited (`429 Too Many Requests`)
+    /// up to `max_retries` times. Before retrying, the client sleeps for as
+    /// long as the `Retry-After` header indicates, or for `retry_base_delay`
+    /// (doubled after every attempt) if it's missing, but never for more than
+    /// a minute.
+    #[must_use]
+    pub fn with_retries(mut self, max_retries: u32, retry_base_delay: Duration) -> Self {
+        self.retry.max_retries = max_retries;
+        self.retry.retry_base_delay = retry_base_delay;
+        self
+    }
+
+    /// Retries the `GET` requests that failed with a transient server error
+    /// (`500`, `502`, `503` or `504`) up to `max_retries` times, sleeping for
+    /// the `retry_base_delay` given to [`Self::with_retries`] (doubled after
+    /// every attempt, up to a minute) before each of them. The rest of the requests are only
+    /// retried if `retry_non_idempotent` is set, since they might have been
+    /// applied in spite of the error. There are no retries by default.
+    #[must_use]
+    pub fn with_server_error_retries(
+        mut self,
+        max_retries: u32,
+        retry_non_idempotent: bool,
+    ) -> Self {
+        self.retry.max_server_error_retries = max_retries;
+        self.retry.retry_non_idempotent = retry_non_idempotent;
+        self
+    }
+
+    /// Randomizes the delays before retrying after server errors between
+    /// their half and their full value, so that many clients failing at once
+    /// don't retry at once too. It's disabled by default.
+    #[must_use]
+    pub fn with_retry_jitter(mut self, jitter: bool) -> Self {
+        self.retry.jitter = jitter;
+        self
+    }
+
+    /// Makes requests fail if they take longer than `timeout`, from the
+    /// moment they're sent until the response is read. There's no timeout by
+    /// default.
+    #[must_use]
+    pub fn with_timeout(mut self, timeout: Option<Duration>) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    /// Invokes `request_hook` after every request performed, including the
+    /// retried ones. There's no hook by default.
+    #[must_use]
+    pub fn with_request_hook(mut self, request_hook: Option<RequestHook>) -> Self {
+        self.request_hook = request_hook;
+        self
+    }
+
+    /// Includes `headers` in every request, like a custom `User-Agent`. The
+    /// headers specific to each request, such as the authorization, take
+    /// precedence over these. Invalid headers are ignored with a warning.
+    #[must_use]
+    pub fn with_default_headers(mut self, headers: &Headers) -> Self {
+        self.default_headers = headers
+            .iter()
+            .filter_map(|(key, val)| {
+                match (
+                    header::HeaderName::from_bytes(key.as_bytes()),
+                    header::HeaderValue::from_str(val),
+                ) {
+                    (Ok(key), Ok(val)) => Some((key, val)),
+                    _ => {
+                        log::warn!("Ignoring invalid header {:?}: {:?}", key, val);
+                        None
+                    }
+                }
+            })
+            .collect();
+        self
+    }
+
+    /// Logs the credentials sent in the requests, like the `Authorization`
+    /// header, instead of replacing them with `***`. This should only be
+    /// enabled for local debugging, and it's disabled by default.
+    #[must_use]
+    pub fn with_log_secrets(mut self, log_secrets: bool) -> Self {
+        self.log_secrets = log_secrets;
+        self
+    }
+
     async fn request<D>(
         &self,
         method: Method,
@@ -66,33 +206,78 @@ impl ReqwestClient {
     where
         D: Fn(RequestBuilder) -> RequestBuilder,
     {
-        let mut request = self.client.request(method.clone(), url);
-
-        // Setting the headers, if any
-        if let Some(headers) = headers {
-            // The headers need to be converted into a `reqwest::HeaderMap`,
-            // which won't fail as long as its contents are ASCII. This is an
-            // internal function, so the condition cannot be broken by the user
-            // and will always be true.
-            //
-            // The content-type header will be set automatically.
-            let headers = headers.try_into().unwrap();
-
-            request = request.headers(headers);
-        }
+        let mut retries = Retries::default();
+        loop {
+            let mut request = self
+                .client
+                .request(method.clone(), url)
+                .headers(self.default_headers.clone());
+            if let Some(timeout) = self.timeout {
+                request = request.timeout(timeout);
+            }
+
+            // Setting the headers, if any
+            if let Some(headers) = headers {
+                // The headers need to be converted into a `reqwest::HeaderMap`,
+                // which won't fail as long as its contents are ASCII. This is
+                // an internal function, so the condition cannot be broken by
+                // the user and will always be true.
+                //
+                // The content-type header will be set automatically, except
+                // for raw bodies.
+                let headers = headers.try_into().unwrap();
 
-        // Configuring the request for the specific type (get/post/put/delete)
-        request = add_data(request);
+                request = request.headers(headers);
+            }
 
-        // Finally performing the request and handling the response
-        log::info!("Making request {:?}", request);
-        let response = request.send().await?;
+            // Configuring the request for the specific type
+            // (get/post/put/delete)
+            request = add_data(request);
 
-        // Making sure that the status code is OK
-        if response.status().is_success() {
-            response.text().await.map_err(Into::into)
-        } else {
-            Err(ReqwestError::StatusCode(response))
+            // Finally performing the request and handling the response
+            let default_headers = self
+                .default_headers
+                .iter()
+                .filter_map(|(key, val)| Some((key.as_str(), val.to_str().ok()?)));
+            let request_headers = headers
+                .into_iter()
+                .flatten()
+                .map(|(key, val)| (key.as_str(), val.as_str()));
+            log_request(
+                method.as_str(),
+                url,
+                default_headers.chain(request_headers),
+                self.log_secrets,
+            );
+            let start = Instant::now();
+            let response = request.send().await;
+            if let Some(hook) = &self.request_hook {
+                hook.call(&RequestInfo {
+                    method: method.as_str(),
+                    url,
+                    status: response.as_ref().ok().map(|res| res.status().as_u16()),
+                    elapsed: start.elapsed(),
+                });
+            }
+            let response = response?;
+
+            // Making sure that the status code is OK
+            if response.status().is_success() {
+                return response.text().await.map_err(Into::into);
+            }
+
+            let retry_after = response
+                .headers()
+                .get(header::RETRY_AFTER)
+                .and_then(|value| value.to_str().ok());
+            let status = response.status().as_u16();
+            match self
+                .retry
+                .retry_delay(method.as_str(), status, retry_after, &mut retries)
+            {
+                Some(delay) => sleep(delay).await,
+                None => return Err(ReqwestError::StatusCode(response)),
+            }
         }
     }
 }
@@ -145,6 +330,19 @@ impl BaseHttpClient for ReqwestClient {
             .await
     }
 
+    #[inline]
+    async fn put_raw(
+        &self,
+        url: &str,
+        headers: Option<&Headers>,
+        payload: &str,
+    ) -> Result<String, Self::Error> {
+        self.request(Method::PUT, url, headers, |req| {
+            req.body(payload.to_owned())
+        })
+        .await
+    }
+
     #[inline]
     async fn delete(
         &self,
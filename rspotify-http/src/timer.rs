@@ -0,0 +1,137 @@
+//! A timer that doesn't rely on a specific async runtime, so that the waits
+//! of the async client, like the ones before retrying a request, work in any
+//! of them.
+//!
+//! A single thread is shared by all the waits: it keeps the pending ones
+//! sorted by their deadline, and wakes each of them up once it's reached.
+
+use std::{
+    cmp::{Ordering, Reverse},
+    collections::BinaryHeap,
+    sync::{
+        mpsc::{self, RecvTimeoutError},
+        Mutex,
+    },
+    thread,
+    time::{Duration, Instant},
+};
+
+use futures_channel::oneshot;
+
+/// The channel to register the waits in the timer thread, which is only
+/// started once it's first needed.
+static TIMER: Mutex<Option<mpsc::Sender<Entry>>> = Mutex::new(None);
+
+/// A pending wait, which is completed by sending through `waker`.
+struct Entry {
+    deadline: Instant,
+    waker: oneshot::Sender<()>,
+}
+
+impl PartialEq for Entry {
+    fn eq(&self, other: &Self) -> bool {
+        self.deadline == other.deadline
+    }
+}
+
+impl Eq for Entry {}
+
+impl PartialOrd for Entry {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Entry {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.deadline.cmp(&other.deadline)
+    }
+}
+
+/// Waits for `duration` without blocking the executor.
+pub async fn sleep(duration: Duration) {
+    // A deadline that can't be represented is never reached
+    let deadline = match Instant::now().checked_add(duration) {
+        Some(deadline) => deadline,
+        None => return std::future::pending().await,
+    };
+
+    let (waker, receiver) = oneshot::channel();
+    register(Entry { deadline, waker });
+    let _ = receiver.await;
+}
+
+fn register(entry: Entry) {
+    let mut timer = TIMER.lock().unwrap_or_else(|err| err.into_inner());
+    // The timer thread may not be running yet, or it may have panicked
+    let entry = match timer.as_ref() {
+        Some(sender) => match sender.send(entry) {
+            Ok(()) => return,
+            Err(mpsc::SendError(entry)) => entry,
+        },
+        None => entry,
+    };
+
+    let (sender, receiver) = mpsc::channel();
+    thread::Builder::new()
+        .name("rspotify-timer".to_owned())
+        .spawn(move || run(receiver))
+        .expect("failed to spawn the timer thread");
+    // The receiver can't have been dropped yet
+    sender.send(entry).unwrap();
+    *timer = Some(sender);
+}
+
+/// The loop of the timer thread, which ends once no more waits can be
+/// registered.
+fn run(receiver: mpsc::Receiver<Entry>) {
+    let mut pending: BinaryHeap<Reverse<Entry>> = BinaryHeap::new();
+    loop {
+        let received = match pending.peek() {
+            Some(Reverse(next)) => {
+                let timeout = next.deadline.saturating_duration_since(Instant::now());
+                receiver.recv_timeout(timeout)
+            }
+            None => receiver.recv().map_err(|_| RecvTimeoutError::Disconnected),
+        };
+        match received {
+            Ok(entry) => {
+                // The waits whose futures were dropped in the meantime are
+                // discarded, since nobody is waiting for them anymore
+                pending.retain(|Reverse(entry)| !entry.waker.is_canceled());
+                pending.push(Reverse(entry));
+            }
+            Err(RecvTimeoutError::Timeout) => {}
+            Err(RecvTimeoutError::Disconnected) => return,
+        }
+
+        let now = Instant::now();
+        while matches!(pending.peek(), Some(Reverse(next)) if next.deadline <= now) {
+            let Reverse(entry) = pending.pop().unwrap();
+            let _ = entry.waker.send(());
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_sleep() {
+        // A shorter wait registered later is completed first
+        let start = Instant::now();
+        let long = async {
+            sleep(Duration::from_millis(100)).await;
+            start.elapsed()
+        };
+        let short = async {
+            sleep(Duration::from_millis(10)).await;
+            start.elapsed()
+        };
+        let (long, short) = tokio::join!(long, short);
+        assert!(long >= Duration::from_millis(100));
+        assert!(short >= Duration::from_millis(10));
+        assert!(short < Duration::from_millis(100));
+    }
+}
@@ -6,10 +6,80 @@ use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
 use crate::{
-    AlbumId, AlbumType, Copyright, DatePrecision, Image, Page, RestrictionReason, SimplifiedArtist,
-    SimplifiedTrack,
+    AlbumId, AlbumType, Copyright, DatePrecision, ExternalIds, Image, Page, RestrictionReason,
+    SimplifiedArtist, SimplifiedTrack,
 };
 
+/// Release date of an album, which may only specify the year or the month
+/// depending on its precision
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct ReleaseDate {
+    year: i32,
+    month: Option<u32>,
+    day: Option<u32>,
+}
+
+impl ReleaseDate {
+    /// Parses a release date formatted as `YYYY`, `YYYY-MM` or `YYYY-MM-DD`,
+    /// as indicated by its precision. It returns `None` if the format is
+    /// invalid, doesn't match the precision, or the date doesn't exist, e.g.
+    /// `1981-13` or `2021-02-30`.
+    #[must_use]
+    pub fn parse(date: &str, precision: DatePrecision) -> Option<Self> {
+        let mut parts = date.split('-');
+        let year = parts.next()?.parse().ok()?;
+        let month = parts.next().map(str::parse).transpose().ok()?;
+        let day = parts.next().map(str::parse).transpose().ok()?;
+        if parts.next().is_some() {
+            return None;
+        }
+
+        let date = Self { year, month, day };
+        if date.precision() != precision {
+            return None;
+        }
+        // Only the dates that actually exist are valid
+        date.as_date()?;
+        Some(date)
+    }
+
+    /// The precision of the date, given by which of its parts are known.
+    #[must_use]
+    pub fn precision(&self) -> DatePrecision {
+        match (self.month, self.day) {
+            (_, Some(_)) => DatePrecision::Day,
+            (Some(_), None) => DatePrecision::Month,
+            (None, None) => DatePrecision::Year,
+        }
+    }
+
+    /// The year, which is always known.
+    #[must_use]
+    pub fn year(&self) -> i32 {
+        self.year
+    }
+
+    /// The month, from 1 to 12, unless the precision is a year.
+    #[must_use]
+    pub fn month(&self) -> Option<u32> {
+        self.month
+    }
+
+    /// The day of the month, if the precision is a day.
+    #[must_use]
+    pub fn day(&self) -> Option<u32> {
+        self.day
+    }
+
+    /// The release date as a calendar date, where the month and day that
+    /// aren't known are the first ones. Since the date is validated when
+    /// parsed, this is only `None` if it can't be represented by `chrono`.
+    #[must_use]
+    pub fn as_date(&self) -> Option<NaiveDate> {
+        NaiveDate::from_ymd_opt(self.year, self.month.unwrap_or(1), self.day.unwrap_or(1))
+    }
+}
+
 /// Simplified Album Object
 #[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq, Default)]
 pub struct SimplifiedAlbum {
@@ -32,6 +102,16 @@ pub struct SimplifiedAlbum {
     pub restrictions: Option<Restriction>,
 }
 
+impl SimplifiedAlbum {
+    /// Parses the album's release date with its precision, if they're
+    /// known.
+    #[must_use]
+    pub fn parsed_release_date(&self) -> Option<ReleaseDate> {
+        let precision = self.release_date_precision.as_deref()?.parse().ok()?;
+        ReleaseDate::parse(self.release_date.as_deref()?, precision)
+    }
+}
+
 /// Full Album Object
 #[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
 pub struct FullAlbum {
@@ -39,7 +119,7 @@ pub struct FullAlbum {
     pub album_type: AlbumType,
     pub available_markets: Option<Vec<String>>,
     pub copyrights: Vec<Copyright>,
-    pub external_ids: HashMap<String, String>,
+    pub external_ids: ExternalIds,
     pub external_urls: HashMap<String, String>,
     pub genres: Vec<String>,
     pub href: String,
@@ -54,6 +134,26 @@ pub struct FullAlbum {
     pub label: Option<String>,
 }
 
+impl FullAlbum {
+    /// Utility to borrow the album's ID.
+    #[must_use]
+    pub fn album_id(&self) -> AlbumId<'_> {
+        self.id.as_ref()
+    }
+
+    /// Parses the album's release date with its precision.
+    #[must_use]
+    pub fn parsed_release_date(&self) -> Option<ReleaseDate> {
+        ReleaseDate::parse(&self.release_date, self.release_date_precision)
+    }
+}
+
+impl<'a> From<&'a FullAlbum> for AlbumId<'a> {
+    fn from(album: &'a FullAlbum) -> Self {
+        album.album_id()
+    }
+}
+
 /// Intermediate full Albums wrapped by Vec object
 #[derive(Deserialize)]
 pub struct FullAlbums {
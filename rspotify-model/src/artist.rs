@@ -28,6 +28,20 @@ pub struct FullArtist {
     pub popularity: u32,
 }
 
+impl FullArtist {
+    /// Utility to borrow the artist's ID.
+    #[must_use]
+    pub fn artist_id(&self) -> ArtistId<'_> {
+        self.id.as_ref()
+    }
+}
+
+impl<'a> From<&'a FullArtist> for ArtistId<'a> {
+    fn from(artist: &'a FullArtist) -> Self {
+        artist.artist_id()
+    }
+}
+
 /// Intermediate full artist object wrapped by `Vec`
 #[derive(Deserialize)]
 pub struct FullArtists {
@@ -40,6 +40,10 @@ pub struct Token {
     // The token response from spotify is singular, hence the rename to `scope`
     #[serde(default, with = "space_separated_scopes", rename = "scope")]
     pub scopes: HashSet<String>,
+    /// Any other fields in the token, like `token_type`, which are kept so
+    /// that they aren't lost when the token is cached and read again.
+    #[serde(flatten)]
+    pub extra: HashMap<String, serde_json::Value>,
 }
 
 impl Default for Token {
@@ -50,6 +54,7 @@ impl Default for Token {
             expires_at: Some(Utc::now()),
             refresh_token: None,
             scopes: HashSet::new(),
+            extra: HashMap::new(),
         }
     }
 }
@@ -80,9 +85,30 @@ impl Token {
     /// is how much a request would take in the worst case scenario).
     #[must_use]
     pub fn is_expired(&self) -> bool {
-        self.expires_at.map_or(true, |expiration| {
-            Utc::now() + Duration::seconds(10) >= expiration
-        })
+        self.is_expired_with_skew(Duration::seconds(10))
+    }
+
+    /// Same as [`Self::is_expired`], but with a custom margin, so that the
+    /// token is considered expired `skew` before it actually expires.
+    #[must_use]
+    pub fn is_expired_with_skew(&self, skew: Duration) -> bool {
+        self.expires_at
+            .map_or(true, |expiration| Utc::now() + skew >= expiration)
+    }
+
+    /// The time left until the token expires, which is negative if it has
+    /// already expired, or `None` if its expiration isn't known.
+    #[must_use]
+    pub fn time_until_expiry(&self) -> Option<Duration> {
+        self.expires_at.map(|expiration| expiration - Utc::now())
+    }
+
+    /// Check if the token has been granted all of the `required` scopes, so
+    /// that a token with fewer scopes than needed (e.g. an old cached one) can
+    /// be detected.
+    #[must_use]
+    pub fn has_scopes(&self, required: &HashSet<String>) -> bool {
+        required.is_subset(&self.scopes)
     }
 
     /// Generates an HTTP token authorization header with proper formatting
@@ -101,6 +127,63 @@ impl Token {
 mod test {
     use crate::Token;
 
+    use std::collections::HashSet;
+
+    use chrono::{Duration, Utc};
+
+    #[test]
+    fn test_has_scopes() {
+        let scopes = |scopes: &[&str]| scopes.iter().map(|s| s.to_string()).collect::<HashSet<_>>();
+        let tok = Token {
+            scopes: scopes(&["user-read-private", "playlist-read-private"]),
+            ..Default::default()
+        };
+
+        assert!(tok.has_scopes(&HashSet::new()));
+        assert!(tok.has_scopes(&scopes(&["user-read-private"])));
+        assert!(tok.has_scopes(&scopes(&["playlist-read-private", "user-read-private"])));
+        assert!(!tok.has_scopes(&scopes(&["user-read-private", "user-library-read"])));
+    }
+
+    #[test]
+    fn test_time_until_expiry() {
+        let tok = Token {
+            expires_at: None,
+            ..Default::default()
+        };
+        assert_eq!(tok.time_until_expiry(), None);
+
+        let tok = Token {
+            expires_at: Some(Utc::now() + Duration::minutes(10)),
+            ..Default::default()
+        };
+        let left = tok.time_until_expiry().unwrap();
+        assert!(left > Duration::minutes(9) && left <= Duration::minutes(10));
+
+        let tok = Token {
+            expires_at: Some(Utc::now() - Duration::minutes(10)),
+            ..Default::default()
+        };
+        assert!(tok.time_until_expiry().unwrap() < Duration::zero());
+    }
+
+    #[test]
+    fn test_unknown_fields() {
+        let json = r#"
+        {
+            "access_token": "access_token",
+            "token_type": "Bearer",
+            "expires_in": 3600,
+            "scope": "user-read-private"
+        }
+        "#;
+        let tok: Token = serde_json::from_str(json).unwrap();
+        assert_eq!(tok.extra.get("token_type"), Some(&"Bearer".into()));
+
+        let tok_str = serde_json::to_string(&tok).unwrap();
+        assert_eq!(serde_json::from_str::<Token>(&tok_str).unwrap(), tok);
+    }
+
     #[test]
     fn test_bearer_auth() {
         let tok = Token {
@@ -1,9 +1,9 @@
 //! All objects related to context
 
 use chrono::{DateTime, Utc};
-use serde::{Deserialize, Deserializer, Serialize};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
 
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use std::time::Duration;
 
 use crate::{
@@ -59,8 +59,9 @@ pub struct CurrentUserQueue {
     pub queue: Vec<PlayableItem>,
 }
 
-/// Actions object
-#[derive(Clone, Debug, Serialize, PartialEq, Eq, Default)]
+/// Actions object. The disallowed actions are sorted, so that their order is
+/// deterministic.
+#[derive(Clone, Debug, PartialEq, Eq, Default)]
 pub struct Actions {
     pub disallows: Vec<DisallowKey>,
 }
@@ -72,7 +73,7 @@ impl<'de> Deserialize<'de> for Actions {
     {
         #[derive(Deserialize)]
         struct OriginalActions {
-            pub disallows: HashMap<DisallowKey, bool>,
+            pub disallows: BTreeMap<DisallowKey, bool>,
         }
         let orignal_actions = OriginalActions::deserialize(deserializer)?;
         Ok(Self {
@@ -85,3 +86,21 @@ impl<'de> Deserialize<'de> for Actions {
         })
     }
 }
+
+// Serialized in the same format Spotify uses, so that it can be deserialized
+// again.
+impl Serialize for Actions {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        #[derive(Serialize)]
+        struct OriginalActions<'a> {
+            pub disallows: BTreeMap<&'a DisallowKey, bool>,
+        }
+        OriginalActions {
+            disallows: self.disallows.iter().map(|key| (key, true)).collect(),
+        }
+        .serialize(serializer)
+    }
+}
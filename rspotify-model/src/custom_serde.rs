@@ -223,7 +223,9 @@ pub mod space_separated_scopes {
     where
         S: Serializer,
     {
-        let scopes = scopes.clone().into_iter().collect::<Vec<_>>().join(" ");
-        s.serialize_str(&scopes)
+        // Sorted so that the output is deterministic
+        let mut scopes = scopes.iter().map(String::as_str).collect::<Vec<_>>();
+        scopes.sort_unstable();
+        s.serialize_str(&scopes.join(" "))
     }
 }
@@ -1,9 +1,11 @@
 use serde::{Deserialize, Serialize};
-use strum::IntoStaticStr;
+use strum::{EnumString, IntoStaticStr};
 
 /// ISO 3166-1 alpha-2 country code, from
 /// [country-list](https://datahub.io/core/country-list)
-#[derive(Clone, Serialize, Deserialize, Copy, PartialEq, Eq, Debug, IntoStaticStr)]
+#[derive(
+    Clone, Serialize, Deserialize, Copy, PartialEq, Eq, Hash, Debug, EnumString, IntoStaticStr,
+)]
 pub enum Country {
     #[strum(serialize = "AF")]
     #[serde(rename = "AF")]
@@ -362,6 +364,10 @@ pub enum Country {
     #[strum(serialize = "KR")]
     #[serde(rename = "KR")]
     KoreaRepublicOf,
+    /// Not an official ISO 3166-1 code, but used by Spotify
+    #[strum(serialize = "XK")]
+    #[serde(rename = "XK")]
+    Kosovo,
     #[strum(serialize = "KW")]
     #[serde(rename = "KW")]
     Kuwait,
@@ -1,9 +1,11 @@
 use serde::{Deserialize, Serialize};
-use strum::{Display, EnumString, IntoStaticStr};
+use strum::{EnumString, IntoStaticStr};
+
+use std::fmt;
 
 /// Copyright type: `C` = the copyright, `P` = the sound recording (performance)
-/// copyright.
-#[derive(Clone, Serialize, Deserialize, Copy, PartialEq, Eq, Debug, IntoStaticStr)]
+/// copyright, or `Unknown` for any other type.
+#[derive(Clone, Serialize, Deserialize, PartialEq, Eq, Debug, IntoStaticStr)]
 pub enum CopyrightType {
     #[strum(serialize = "P")]
     #[serde(rename = "P")]
@@ -11,10 +13,15 @@ pub enum CopyrightType {
     #[strum(serialize = "C")]
     #[serde(rename = "C")]
     Copyright,
+    /// Any value not known by rspotify yet, so that new ones added by
+    /// Spotify don't make the whole response fail to deserialize. It holds
+    /// the value as is, so that it's serialized back unchanged.
+    #[serde(untagged)]
+    Unknown(String),
 }
 
 /// Album type: `album`, `single`, `appears_on`, `compilation`
-#[derive(Clone, Serialize, Deserialize, Copy, PartialEq, Eq, Debug, IntoStaticStr)]
+#[derive(Clone, Serialize, Deserialize, PartialEq, Eq, Debug, IntoStaticStr)]
 #[serde(rename_all = "snake_case")]
 #[strum(serialize_all = "snake_case")]
 pub enum AlbumType {
@@ -22,12 +29,16 @@ pub enum AlbumType {
     Single,
     AppearsOn,
     Compilation,
+    /// Any value not known by rspotify yet, so that new ones added by
+    /// Spotify don't make the whole response fail to deserialize. It holds
+    /// the value as is, so that it's serialized back unchanged.
+    #[serde(untagged)]
+    Unknown(String),
 }
 
-/// Type: `artist`, `album`, `track`, `playlist`, `show` or `episode`
-#[derive(
-    Clone, Serialize, Deserialize, Copy, PartialEq, Eq, Debug, Display, EnumString, IntoStaticStr,
-)]
+/// Type: `artist`, `album`, `track`, `playlist`, `show`, `episode`,
+/// `audiobook` or `chapter`
+#[derive(Clone, Serialize, Deserialize, PartialEq, Eq, Debug, EnumString, IntoStaticStr)]
 #[serde(rename_all = "snake_case")]
 #[strum(serialize_all = "snake_case")]
 pub enum Type {
@@ -38,7 +49,23 @@ pub enum Type {
     User,
     Show,
     Episode,
+    Audiobook,
+    Chapter,
     Collection,
+    /// Any value not known by rspotify yet, so that new ones added by
+    /// Spotify don't make the whole response fail to deserialize. It holds
+    /// the value as is, so that it's serialized back unchanged.
+    #[serde(untagged)]
+    Unknown(String),
+}
+
+impl fmt::Display for Type {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Unknown(value) => f.write_str(value),
+            known => f.write_str(known.into()),
+        }
+    }
 }
 
 /// Additional typs: `track`, `episode`
@@ -51,7 +78,7 @@ pub enum AdditionalType {
 }
 
 /// Currently playing type: `track`, `episode`, `ad`, `unknown`
-#[derive(Clone, Serialize, Deserialize, Copy, PartialEq, Eq, Debug, IntoStaticStr)]
+#[derive(Clone, Serialize, Deserialize, PartialEq, Eq, Debug, IntoStaticStr)]
 #[serde(rename_all = "snake_case")]
 #[strum(serialize_all = "snake_case")]
 pub enum CurrentlyPlayingType {
@@ -60,7 +87,11 @@ pub enum CurrentlyPlayingType {
     #[strum(serialize = "ad")]
     #[serde(rename = "ad")]
     Advertisement,
-    Unknown,
+    /// Any value not known by rspotify yet, so that new ones added by
+    /// Spotify don't make the whole response fail to deserialize. It holds
+    /// the value as is, so that it's serialized back unchanged.
+    #[serde(untagged)]
+    Unknown(String),
 }
 
 /// Type for search: `artist`, `album`, `track`, `playlist`, `show`, `episode`
@@ -109,7 +140,11 @@ pub enum DeviceType {
     CastVideo,
     CastAudio,
     Automobile,
-    Unknown,
+    /// Any value not known by rspotify yet, so that new ones added by
+    /// Spotify don't make the whole response fail to deserialize. It holds
+    /// the value as is, so that it's serialized back unchanged.
+    #[serde(untagged)]
+    Unknown(String),
 }
 
 /// Recommendations seed type
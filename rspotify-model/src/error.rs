@@ -1,28 +1,109 @@
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
+use strum::IntoStaticStr;
 use thiserror::Error;
 
+use std::time::Duration;
+
 pub type ApiResult<T> = Result<T, ApiError>;
 pub type ModelResult<T> = Result<T, ModelError>;
 
 /// Matches errors that are returned from the Spotfiy
 /// API as part of the JSON response object.
-#[derive(Debug, Error, Deserialize)]
-pub enum ApiError {
-    /// See [Error Object](https://developer.spotify.com/documentation/web-api/reference/#object-errorobject)
-    #[error("{status}: {message}")]
-    #[serde(alias = "error")]
-    Regular { status: u16, message: String },
-
-    /// See [Play Error Object](https://developer.spotify.com/documentation/web-api/reference/#object-playererrorobject)
-    #[error("{status} ({reason}): {message}")]
-    #[serde(alias = "error")]
-    Player {
-        status: u16,
-        message: String,
-        reason: String,
+///
+/// Spotify reports errors with a [Regular Error Object], or with a [Player
+/// Error Object] in the player endpoints, which also includes a `reason`.
+/// Both are deserialized into this struct. The authentication endpoints
+/// follow a different format that doesn't include the status code, so
+/// [`ApiError::from_response`] should be preferred to parse the body of
+/// unsuccessful responses.
+///
+/// [Regular Error Object]: https://developer.spotify.com/documentation/web-api/reference/#object-errorobject
+/// [Player Error Object]: https://developer.spotify.com/documentation/web-api/reference/#object-playererrorobject
+#[derive(Clone, Debug, Error, Serialize, Deserialize, PartialEq, Eq)]
+#[error("{status}: {message}")]
+pub struct ApiError {
+    pub status: u16,
+    pub message: String,
+    pub reason: Option<PlayerErrorReason>,
+    /// How long Spotify asked to wait before making more requests, from the
+    /// `Retry-After` header of `429 Too Many Requests` responses. It's only
+    /// available when the error was obtained from a client's request.
+    #[serde(skip)]
+    pub retry_after: Option<Duration>,
+}
+
+/// The body of an unsuccessful response, in any of the formats Spotify may
+/// use.
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum ApiErrorPayload {
+    Regular {
+        error: ApiError,
+    },
+    Auth {
+        error: String,
+        error_description: Option<String>,
     },
 }
 
+impl ApiError {
+    /// Parses the body of an unsuccessful response with the given status
+    /// code. In case the body isn't a known error object, it's used as the
+    /// message itself.
+    #[must_use]
+    pub fn from_response(status: u16, body: &str) -> Self {
+        match serde_json::from_str(body) {
+            Ok(ApiErrorPayload::Regular { error }) => error,
+            Ok(ApiErrorPayload::Auth {
+                error,
+                error_description,
+            }) => Self {
+                status,
+                message: match error_description {
+                    Some(description) => format!("{error}: {description}"),
+                    None => error,
+                },
+                reason: None,
+                retry_after: None,
+            },
+            Err(_) => Self {
+                status,
+                message: body.to_owned(),
+                reason: None,
+                retry_after: None,
+            },
+        }
+    }
+}
+
+/// Reason of a [Player Error Object], e.g. `PREMIUM_REQUIRED`
+///
+/// [Player Error Object]: https://developer.spotify.com/documentation/web-api/reference/#object-playererrorobject
+#[derive(Clone, Copy, Debug, Serialize, Deserialize, PartialEq, Eq, IntoStaticStr)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+#[strum(serialize_all = "SCREAMING_SNAKE_CASE")]
+pub enum PlayerErrorReason {
+    NoPrevTrack,
+    NoNextTrack,
+    NoSpecificTrack,
+    AlreadyPaused,
+    NotPaused,
+    NotPlayingLocally,
+    NotPlayingTrack,
+    NotPlayingContext,
+    EndlessContext,
+    ContextDisallow,
+    AlreadyPlaying,
+    RateLimited,
+    RemoteControlDisallow,
+    DeviceNotControllable,
+    VolumeControlDisallow,
+    NoActiveDevice,
+    PremiumRequired,
+    #[serde(other)]
+    Unknown,
+}
+
 /// Groups up the kinds of errors that may happen in this crate.
 #[derive(Debug, Error)]
 pub enum ModelError {
@@ -15,6 +15,8 @@
 //! * [`Type::User`] => [`UserId`]
 //! * [`Type::Show`] => [`ShowId`]
 //! * [`Type::Episode`] => [`EpisodeId`]
+//! * [`Type::Audiobook`] => [`AudiobookId`]
+//! * [`Type::Chapter`] => [`ChapterId`]
 //!
 //! Every kind of ID defines its own validity function, i.e., what characters it
 //! can be made up of, such as alphanumeric or any.
@@ -176,8 +178,39 @@ pub fn parse_uri(uri: &str) -> Result<(Type, &str), IdError> {
     // Note that in case the type isn't known at compile time,
     // any type will be accepted.
     match tpe.parse::<Type>() {
+        Ok(Type::Unknown(_)) | Err(_) => Err(IdError::InvalidType),
         Ok(tpe) => Ok((tpe, &id[1..])),
-        _ => Err(IdError::InvalidType),
+    }
+}
+
+/// A lower level function to parse a Spotify URL (e.g.,
+/// `https://open.spotify.com/track/4y4VO05kYgUTo2bzbox1an?si=...`) into both
+/// its type and its actual ID. Query parameters are ignored, and so is the
+/// locale segment of localized URLs (e.g., `/intl-es/`). As with
+/// [`parse_uri`], the validity of the returned ID isn't checked.
+pub fn parse_url(url: &str) -> Result<(Type, &str), IdError> {
+    let path = url
+        .strip_prefix("https://open.spotify.com/")
+        .or_else(|| url.strip_prefix("http://open.spotify.com/"))
+        .ok_or(IdError::InvalidPrefix)?;
+    let path = match path.find(['?', '#']) {
+        Some(end) => &path[..end],
+        None => path,
+    };
+
+    let mut segments = path.split('/');
+    let mut tpe = segments.next().ok_or(IdError::InvalidFormat)?;
+    if tpe.starts_with("intl-") {
+        tpe = segments.next().ok_or(IdError::InvalidFormat)?;
+    }
+    let id = segments.next().ok_or(IdError::InvalidFormat)?;
+    if segments.next().is_some() {
+        return Err(IdError::InvalidFormat);
+    }
+
+    match tpe.parse::<Type>() {
+        Ok(Type::Unknown(_)) | Err(_) => Err(IdError::InvalidType),
+        Ok(tpe) => Ok((tpe, id)),
     }
 }
 
@@ -255,8 +288,8 @@ macro_rules! define_idtypes {
                 /// Spotify URI must be in one of the following formats:
                 /// `spotify:{type}:{id}` or `spotify/{type}/{id}`.
                 /// Where `{type}` is one of `artist`, `album`, `track`,
-                /// `playlist`, `user`, `show`, or `episode`, and `{id}` is a
-                /// non-empty valid string.
+                /// `playlist`, `user`, `show`, `episode`, `audiobook` or
+                /// `chapter`, and `{id}` is a non-empty valid string.
                 ///
                 /// Examples: `spotify:album:6IcGNaXFRf5Y1jc7QsE9O2`,
                 /// `spotify/track/4y4VO05kYgUTo2bzbox1an`.
@@ -289,21 +322,53 @@ macro_rules! define_idtypes {
                     }
                 }
 
-                /// Parse Spotify ID or URI from string slice
+                /// Parse Spotify URL from string slice
+                ///
+                /// Spotify URL must be in the format
+                /// `https://open.spotify.com/{type}/{id}`, optionally followed
+                /// by query parameters such as `?si=...`, which are ignored.
+                ///
+                /// Examples: `https://open.spotify.com/album/6IcGNaXFRf5Y1jc7QsE9O2`,
+                /// `https://open.spotify.com/track/4y4VO05kYgUTo2bzbox1an?si=abc`.
+                ///
+                /// # Errors
+                ///
+                /// - `IdError::InvalidPrefix` - if `url` doesn't start with
+                ///   `https://open.spotify.com/`,
+                /// - `IdError::InvalidType` - if type part of an `url` is not a
+                ///   valid Spotify type `T`,
+                /// - `IdError::InvalidId` - if id part of an `url` is not a
+                ///   valid id,
+                /// - `IdError::InvalidFormat` - if it can't be splitted into
+                ///   type and id parts.
+                pub fn from_url(url: &'a str) -> Result<Self, IdError> {
+                    let (tpe, id) = parse_url(&url)?;
+                    if tpe == Type::$type {
+                        Self::from_id(id)
+                    } else {
+                        Err(IdError::InvalidType)
+                    }
+                }
+
+                /// Parse Spotify ID, URI or URL from string slice
                 ///
                 /// Spotify URI must be in one of the following formats:
                 /// `spotify:{type}:{id}` or `spotify/{type}/{id}`.
                 /// Where `{type}` is one of `artist`, `album`, `track`,
-                /// `playlist`, `user`, `show`, or `episode`, and `{id}` is a
-                /// non-empty valid string. The URI must be match with the ID's
-                /// type (`Id::TYPE`), otherwise `IdError::InvalidType` error is
-                /// returned.
+                /// `playlist`, `user`, `show`, `episode`, `audiobook` or
+                /// `chapter`, and `{id}` is a non-empty valid string. The URI
+                /// must be match with the ID's type (`Id::TYPE`), otherwise
+                /// `IdError::InvalidType` error is returned.
                 ///
                 /// Examples: `spotify:album:6IcGNaXFRf5Y1jc7QsE9O2`,
                 /// `spotify/track/4y4VO05kYgUTo2bzbox1an`.
                 ///
-                /// If input string is not a valid Spotify URI (it's not started
-                /// with `spotify:` or `spotify/`), it must be a valid Spotify
+                /// Spotify URLs like `https://open.spotify.com/{type}/{id}` are
+                /// accepted as well; see [`Self::from_url`].
+                ///
+                /// If input string is not a valid Spotify URI or URL (it's not
+                /// started with `spotify:`, `spotify/` or
+                /// `https://open.spotify.com/`), it must be a valid Spotify
                 /// object ID, i.e. a non-empty valid string.
                 ///
                 /// # Errors
@@ -327,7 +392,11 @@ macro_rules! define_idtypes {
                 pub fn from_id_or_uri(id_or_uri: &'a str) -> Result<Self, IdError> {
                     match Self::from_uri(id_or_uri) {
                         Ok(id) => Ok(id),
-                        Err(IdError::InvalidPrefix) => Self::from_id(id_or_uri),
+                        Err(IdError::InvalidPrefix) => match Self::from_url(id_or_uri) {
+                            Ok(id) => Ok(id),
+                            Err(IdError::InvalidPrefix) => Self::from_id(id_or_uri),
+                            Err(error) => Err(error),
+                        },
                         Err(error) => Err(error),
                     }
                 }
@@ -423,6 +492,16 @@ macro_rules! define_idtypes {
                 }
             }
 
+            /// Parsing an ID with `TryFrom` accepts IDs, URIs and URLs, like
+            /// `from_id_or_uri`.
+            impl<'a> std::convert::TryFrom<&'a str> for $name<'a> {
+                type Error = IdError;
+
+                fn try_from(id_or_uri: &'a str) -> Result<Self, Self::Error> {
+                    Self::from_id_or_uri(id_or_uri)
+                }
+            }
+
             /// `Id`s may be borrowed as `str` the same way `Box<T>` may be
             /// borrowed as `T` or `String` as `str`
             impl std::borrow::Borrow<str> for $name<'_> {
@@ -468,6 +547,14 @@ define_idtypes!(
         name: EpisodeId,
         validity: |id| id.chars().all(|ch| ch.is_ascii_alphanumeric())
     },
+    Audiobook => {
+        name: AudiobookId,
+        validity: |id| id.chars().all(|ch| ch.is_ascii_alphanumeric())
+    },
+    Chapter => {
+        name: ChapterId,
+        validity: |id| id.chars().all(|ch| ch.is_ascii_alphanumeric())
+    },
     User => {
         name: UserId,
         validity: |_| true
@@ -567,6 +654,13 @@ mod test {
     const URI_SHORT: &str = "track:4iV5W9uYEdYUVa79Axb7Rh";
     const URI_MIXED1: &str = "spotify/track:4iV5W9uYEdYUVa79Axb7Rh";
     const URI_MIXED2: &str = "spotify:track/4iV5W9uYEdYUVa79Axb7Rh";
+    const URL: &str = "https://open.spotify.com/track/4iV5W9uYEdYUVa79Axb7Rh";
+    const URL_QUERY: &str = "https://open.spotify.com/track/4iV5W9uYEdYUVa79Axb7Rh?si=1a2b3c";
+    const URL_INTL: &str = "https://open.spotify.com/intl-es/track/4iV5W9uYEdYUVa79Axb7Rh";
+    // Invalid URLs:
+    const URL_WRONGTYPE: &str = "https://open.spotify.com/album/4iV5W9uYEdYUVa79Axb7Rh";
+    const URL_NOID: &str = "https://open.spotify.com/track";
+    const URL_EXTRA: &str = "https://open.spotify.com/track/4iV5W9uYEdYUVa79Axb7Rh/extra";
 
     #[test]
     fn test_id_parse() {
@@ -592,6 +686,28 @@ mod test {
         assert_eq!(TrackId::from_uri(URI_MIXED2), Err(IdError::InvalidFormat));
     }
 
+    #[test]
+    fn test_url_parse() {
+        assert_eq!(TrackId::from_url(URL).unwrap().id(), ID);
+        assert_eq!(TrackId::from_url(URL_QUERY).unwrap().id(), ID);
+        assert_eq!(TrackId::from_url(URL_INTL).unwrap().id(), ID);
+        assert_eq!(TrackId::from_url(ID), Err(IdError::InvalidPrefix));
+        assert_eq!(TrackId::from_url(URI), Err(IdError::InvalidPrefix));
+        assert_eq!(TrackId::from_url(URL_WRONGTYPE), Err(IdError::InvalidType));
+        assert_eq!(TrackId::from_url(URL_NOID), Err(IdError::InvalidFormat));
+        assert_eq!(TrackId::from_url(URL_EXTRA), Err(IdError::InvalidFormat));
+    }
+
+    #[test]
+    fn test_try_from() {
+        use std::convert::TryFrom;
+
+        for input in [ID, URI, URL_QUERY] {
+            assert_eq!(TrackId::try_from(input).unwrap().id(), ID);
+        }
+        assert_eq!(TrackId::try_from(URL_WRONGTYPE), Err(IdError::InvalidType));
+    }
+
     /// Deserialization should accept both IDs and URIs as well.
     #[test]
     fn test_id_or_uri_and_deserialize() {
@@ -608,6 +724,8 @@ mod test {
             assert_eq!(check(URI).unwrap().id(), ID);
             assert!(check(URI_SLASHES).is_ok());
             assert_eq!(check(URI_SLASHES).unwrap().id(), ID);
+            assert!(check(URL).is_ok());
+            assert_eq!(check(URL).unwrap().id(), ID);
 
             // These should not work in any case
             assert!(check(URI_SHORT).is_err());
@@ -4,6 +4,7 @@
 pub mod album;
 pub mod artist;
 pub mod audio;
+pub mod audiobook;
 pub mod auth;
 pub mod category;
 pub mod context;
@@ -24,9 +25,9 @@ pub mod track;
 pub mod user;
 
 pub use {
-    album::*, artist::*, audio::*, auth::*, category::*, context::*, device::*, enums::*, error::*,
-    idtypes::*, image::*, offset::*, page::*, playing::*, playlist::*, recommend::*, search::*,
-    show::*, track::*, user::*,
+    album::*, artist::*, audio::*, audiobook::*, auth::*, category::*, context::*, device::*,
+    enums::*, error::*, idtypes::*, image::*, offset::*, page::*, playing::*, playlist::*,
+    recommend::*, search::*, show::*, track::*, user::*,
 };
 
 use serde::{Deserialize, Serialize};
@@ -39,6 +40,12 @@ pub struct Followers {
     pub total: u32,
 }
 
+/// Intermediate available markets object wrapped by `Vec`
+#[derive(Deserialize)]
+pub struct MarketsPayload {
+    pub markets: Vec<Country>,
+}
+
 /// A full track object or a full episode object
 #[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
 #[serde(untagged)]
@@ -61,6 +68,19 @@ impl PlayableItem {
     }
 }
 
+/// Any of the objects a Spotify URI or URL may point to.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub enum ResolvedItem {
+    Track(track::FullTrack),
+    Album(album::FullAlbum),
+    Artist(artist::FullArtist),
+    Playlist(playlist::FullPlaylist),
+    Show(show::FullShow),
+    Episode(show::FullEpisode),
+    Audiobook(audiobook::Audiobook),
+    User(user::PublicUser),
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -14,6 +14,31 @@ pub struct Page<T> {
     pub total: u32,
 }
 
+impl<T> Page<T> {
+    /// Transforms the items in the page with `f`, keeping the rest of its
+    /// fields, like `total` and `next`.
+    pub fn map<U, F>(self, f: F) -> Page<U>
+    where
+        F: FnMut(T) -> U,
+    {
+        Page {
+            href: self.href,
+            items: self.items.into_iter().map(f).collect(),
+            limit: self.limit,
+            next: self.next,
+            offset: self.offset,
+            previous: self.previous,
+            total: self.total,
+        }
+    }
+
+    /// Whether this is the last page, i.e., there's no `next` one.
+    #[must_use]
+    pub fn is_last(&self) -> bool {
+        self.next.is_none()
+    }
+}
+
 /// Cursor-based paging object
 #[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq, Default)]
 pub struct CursorBasedPage<T> {
@@ -31,4 +56,29 @@ pub struct CursorBasedPage<T> {
 #[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq, Default)]
 pub struct Cursor {
     pub after: Option<String>,
+    pub before: Option<String>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_page_map() {
+        let page = Page {
+            items: vec![1, 2, 3],
+            next: Some("https://api.spotify.com/v1/me/tracks?offset=3".to_owned()),
+            total: 6,
+            ..Default::default()
+        };
+        assert!(!page.is_last());
+
+        let page = page.map(|item| item.to_string());
+        assert_eq!(page.items, ["1", "2", "3"]);
+        assert_eq!(page.total, 6);
+        assert!(!page.is_last());
+
+        let page = Page { next: None, ..page };
+        assert!(page.is_last());
+    }
 }
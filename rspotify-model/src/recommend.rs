@@ -12,6 +12,12 @@ pub struct Recommendations {
     pub tracks: Vec<SimplifiedTrack>,
 }
 
+/// Intermediate available genre seeds object wrapped by `Vec`
+#[derive(Deserialize)]
+pub struct GenreSeedsPayload {
+    pub genres: Vec<String>,
+}
+
 /// Recommendations seed object
 #[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
 pub struct RecommendationsSeed {
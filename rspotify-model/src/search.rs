@@ -60,3 +60,15 @@ pub enum SearchResult {
     #[serde(rename = "episodes")]
     Episodes(Page<SimplifiedEpisode>),
 }
+
+/// Search results of several kinds at once, as returned when searching for
+/// multiple types. Only the requested types will be present.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq, Default)]
+pub struct SearchResults {
+    pub playlists: Option<Page<SimplifiedPlaylist>>,
+    pub albums: Option<Page<SimplifiedAlbum>>,
+    pub artists: Option<Page<FullArtist>>,
+    pub tracks: Option<Page<FullTrack>>,
+    pub shows: Option<Page<SimplifiedShow>>,
+    pub episodes: Option<Page<SimplifiedEpisode>>,
+}
@@ -1,3 +1,4 @@
+use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 
 use std::collections::HashMap;
@@ -42,7 +43,7 @@ pub struct SeversalSimplifiedShows {
 /// Saved show object
 #[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
 pub struct Show {
-    pub added_at: String,
+    pub added_at: DateTime<Utc>,
     pub show: SimplifiedShow,
 }
 
@@ -116,6 +117,27 @@ pub struct FullEpisode {
     pub show: SimplifiedShow,
 }
 
+impl FullEpisode {
+    /// Utility to borrow the episode's ID.
+    #[must_use]
+    pub fn episode_id(&self) -> EpisodeId<'_> {
+        self.id.as_ref()
+    }
+}
+
+impl<'a> From<&'a FullEpisode> for EpisodeId<'a> {
+    fn from(episode: &'a FullEpisode) -> Self {
+        episode.episode_id()
+    }
+}
+
+/// Saved episode object
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub struct SavedEpisode {
+    pub added_at: DateTime<Utc>,
+    pub episode: FullEpisode,
+}
+
 /// Intermediate episodes feature object wrapped by `Vec`
 #[derive(Deserialize)]
 pub struct EpisodesPayload {
@@ -3,12 +3,31 @@
 use chrono::prelude::*;
 use serde::{Deserialize, Serialize};
 
-use std::{collections::HashMap, time::Duration};
+use std::{
+    collections::{HashMap, HashSet},
+    time::Duration,
+};
 
 use crate::{
-    custom_serde::duration_ms, PlayableId, Restriction, SimplifiedAlbum, SimplifiedArtist, TrackId,
+    custom_serde::duration_ms, Country, PlayableId, Restriction, SimplifiedAlbum, SimplifiedArtist,
+    TrackId,
 };
 
+/// External IDs object, which identifies a track or an album outside of
+/// Spotify
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq, Default)]
+pub struct ExternalIds {
+    /// International Standard Recording Code
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub isrc: Option<String>,
+    /// International Article Number
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ean: Option<String>,
+    /// Universal Product Code
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub upc: Option<String>,
+}
+
 /// Full track object
 #[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
 pub struct FullTrack {
@@ -20,7 +39,7 @@ pub struct FullTrack {
     #[serde(with = "duration_ms", rename = "duration_ms")]
     pub duration: Duration,
     pub explicit: bool,
-    pub external_ids: HashMap<String, String>,
+    pub external_ids: ExternalIds,
     pub external_urls: HashMap<String, String>,
     pub href: Option<String>,
     /// Note that a track may not have an ID/URI if it's local
@@ -38,6 +57,70 @@ pub struct FullTrack {
     pub track_number: u32,
 }
 
+impl FullTrack {
+    /// Utility to borrow the track's ID, which may not be present if it's
+    /// local.
+    #[must_use]
+    pub fn track_id(&self) -> Option<TrackId<'_>> {
+        self.id.as_ref().map(TrackId::as_ref)
+    }
+
+    /// The ID of the track that is actually played, which is a different
+    /// one from the requested track if relinking was applied. This is the one
+    /// that should be used for playback.
+    #[must_use]
+    pub fn playable_id(&self) -> Option<PlayableId<'_>> {
+        self.track_id().map(PlayableId::Track)
+    }
+
+    /// The ID of the track that was originally requested, which is the one in
+    /// `linked_from` if relinking was applied, or the track's own ID
+    /// otherwise.
+    #[must_use]
+    pub fn original_id(&self) -> Option<TrackId<'_>> {
+        match &self.linked_from {
+            Some(link) => Some(link.id.as_ref()),
+            None => self.track_id(),
+        }
+    }
+
+    /// Whether the track is available in the given country, according to
+    /// `available_markets`.
+    ///
+    /// This scans the whole list of markets, so when checking many countries
+    /// it's cheaper to build a set once with [`Self::available_markets_set`].
+    ///
+    /// Note that when the track was requested with a market, relinking is
+    /// applied and Spotify doesn't include `available_markets`, so this always
+    /// returns `false`. In that case `is_playable` should be checked instead,
+    /// and `linked_from` holds the original track if it was relinked.
+    #[must_use]
+    pub fn is_available_in(&self, country: Country) -> bool {
+        let code: &str = country.into();
+        self.available_markets.iter().any(|market| market == code)
+    }
+
+    /// The countries in `available_markets` as a set, to check the
+    /// availability in many countries without scanning the list each time.
+    /// The codes that aren't known by [`Country`] are skipped.
+    ///
+    /// Just like with [`Self::is_available_in`], this is empty when the track
+    /// was requested with a market, since relinking is applied then.
+    #[must_use]
+    pub fn available_markets_set(&self) -> HashSet<Country> {
+        self.available_markets
+            .iter()
+            .filter_map(|market| market.parse().ok())
+            .collect()
+    }
+}
+
+impl<'a> From<&'a FullTrack> for Option<TrackId<'a>> {
+    fn from(track: &'a FullTrack) -> Self {
+        track.track_id()
+    }
+}
+
 /// Track link object
 #[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
 pub struct TrackLink {
@@ -84,6 +167,15 @@ pub struct SavedTrack {
     pub track: FullTrack,
 }
 
+impl SavedTrack {
+    /// Sorts the saved tracks by the time they were added, from oldest to
+    /// newest, since Spotify returns the newest ones first. Tracks added at
+    /// the same time keep their order.
+    pub fn sort_by_added_at(tracks: &mut [SavedTrack]) {
+        tracks.sort_by_key(|track| track.added_at);
+    }
+}
+
 /// Track id with specific positions track in a playlist
 ///
 /// This is a short-lived struct for endpoint parameters, so it uses
@@ -1,5 +1,5 @@
 use crate::{
-    auth_urls,
+    auth_urls, check_extra_params,
     clients::{BaseClient, OAuthClient},
     http::{Form, HttpClient},
     join_scopes, params,
@@ -53,6 +53,10 @@ use url::Url;
 ///
 /// ![demo](https://raw.githubusercontent.com/ramsayleung/rspotify/master/doc/images/rspotify.gif)
 ///
+/// The client can be cloned cheaply to share it between tasks or threads. The
+/// clones share the same token, so refreshing it from one of them also takes
+/// effect in the rest.
+///
 /// Note: even if your script does not have an accessible URL, you will have to
 /// specify a redirect URI. It doesn't need to work, you can use
 /// `http://localhost:8888/callback` for example, which will also have the code
@@ -69,6 +73,9 @@ pub struct AuthCodeSpotify {
     pub config: Config,
     pub token: Arc<Mutex<Option<Token>>>,
     pub(crate) http: HttpClient,
+    /// Held while the token is being refreshed automatically, so that it's
+    /// only refreshed once at a time.
+    pub(crate) refresh_lock: Arc<Mutex<()>>,
 }
 
 /// This client has access to the base methods.
@@ -82,6 +89,10 @@ impl BaseClient for AuthCodeSpotify {
         Arc::clone(&self.token)
     }
 
+    fn get_refresh_lock(&self) -> Arc<Mutex<()>> {
+        Arc::clone(&self.refresh_lock)
+    }
+
     fn get_creds(&self) -> &Credentials {
         &self.creds
     }
@@ -107,7 +118,11 @@ impl BaseClient for AuthCodeSpotify {
                     .auth_headers()
                     .expect("No client secret set in the credentials.");
                 let mut token = self.fetch_access_token(&data, Some(&headers)).await?;
-                token.refresh_token = Some(refresh_token.to_string());
+                // Spotify may rotate the refresh token, in which case the new
+                // one has to be kept, since the previous one may be revoked.
+                token
+                    .refresh_token
+                    .get_or_insert_with(|| refresh_token.to_string());
                 Ok(Some(token))
             }
             _ => Ok(None),
@@ -179,6 +194,7 @@ impl AuthCodeSpotify {
         Self {
             creds,
             oauth,
+            http: config.build_http(),
             config,
             ..Default::default()
         }
@@ -187,8 +203,63 @@ impl AuthCodeSpotify {
     /// Returns the URL needed to authorize the current client as the first step
     /// in the authorization flow.
     pub fn get_authorize_url(&self, show_dialog: bool) -> ClientResult<String> {
+        self.build_authorize_url(&self.oauth.state, show_dialog, &[])
+    }
+
+    /// Same as [`Self::get_authorize_url`], but `extra_params` are appended
+    /// to the query string, e.g. the parameters supported by Spotify that
+    /// aren't available here yet.
+    ///
+    /// The parameters set by RSpotify itself, like `state` or `show_dialog`,
+    /// can't be overridden; in that case [`ClientError::InvalidInput`] is
+    /// returned.
+    ///
+    /// [`ClientError::InvalidInput`]: crate::ClientError::InvalidInput
+    pub fn get_authorize_url_with_params(
+        &self,
+        show_dialog: bool,
+        extra_params: &[(&str, &str)],
+    ) -> ClientResult<String> {
+        self.build_authorize_url(&self.oauth.state, show_dialog, extra_params)
+    }
+
+    /// Same as [`Self::get_authorize_url`], but with an explicit `state`
+    /// instead of the one in [`OAuth::state`]. This is useful when a single
+    /// client is shared by a server handling multiple users, each with its
+    /// own state.
+    ///
+    /// Note that [`parse_response_code`](OAuthClient::parse_response_code)
+    /// checks the callback against [`OAuth::state`], so the state in the
+    /// callback has to be verified manually instead.
+    pub fn get_authorize_url_with_state(
+        &self,
+        state: &str,
+        show_dialog: bool,
+    ) -> ClientResult<String> {
+        self.build_authorize_url(state, show_dialog, &[])
+    }
+
+    /// The shared logic of the methods building the authorization URL.
+    fn build_authorize_url(
+        &self,
+        state: &str,
+        show_dialog: bool,
+        extra_params: &[(&str, &str)],
+    ) -> ClientResult<String> {
         log::info!("Building auth URL");
 
+        check_extra_params(
+            extra_params,
+            &[
+                params::CLIENT_ID,
+                params::RESPONSE_TYPE,
+                params::REDIRECT_URI,
+                params::SCOPE,
+                params::STATE,
+                params::SHOW_DIALOG,
+            ],
+        )?;
+
         let scopes = join_scopes(&self.oauth.scopes);
 
         let mut payload: HashMap<&str, &str> = HashMap::new();
@@ -196,13 +267,17 @@ impl AuthCodeSpotify {
         payload.insert(params::RESPONSE_TYPE, params::RESPONSE_TYPE_CODE);
         payload.insert(params::REDIRECT_URI, &self.oauth.redirect_uri);
         payload.insert(params::SCOPE, &scopes);
-        payload.insert(params::STATE, &self.oauth.state);
+        payload.insert(params::STATE, state);
 
         if show_dialog {
             payload.insert(params::SHOW_DIALOG, "true");
         }
 
-        let parsed = Url::parse_with_params(auth_urls::AUTHORIZE, payload)?;
+        let params = payload.into_iter().chain(extra_params.iter().copied());
+        let parsed = Url::parse_with_params(
+            &(self.config.auth_prefix.clone() + auth_urls::AUTHORIZE),
+            params,
+        )?;
         Ok(parsed.into())
     }
 }
@@ -1,5 +1,5 @@
 use crate::{
-    alphabets, auth_urls,
+    alphabets, auth_urls, check_extra_params,
     clients::{BaseClient, OAuthClient},
     generate_random_string,
     http::{Form, HttpClient},
@@ -25,6 +25,11 @@ use url::Url;
 /// However, note that the refresh token obtained with PKCE will only work to
 /// request the next one, after which it'll become invalid.
 ///
+/// The code verifier is generated and stored by [`Self::get_authorize_url`],
+/// and [`OAuthClient::request_token`] sends it to Spotify when exchanging the
+/// code, so that the whole flow (including `prompt_for_token` with the `cli`
+/// feature) only requires calling them in order.
+///
 /// There's an [example][example-main] available to learn how to use this
 /// client.
 ///
@@ -36,9 +41,13 @@ pub struct AuthCodePkceSpotify {
     pub oauth: OAuth,
     pub config: Config,
     pub token: Arc<Mutex<Option<Token>>>,
-    /// The code verifier for the authentication process
+    /// The code verifier for the authentication process, set by
+    /// [`Self::get_authorize_url`]
     pub verifier: Option<String>,
     pub(crate) http: HttpClient,
+    /// Held while the token is being refreshed automatically, so that it's
+    /// only refreshed once at a time.
+    pub(crate) refresh_lock: Arc<Mutex<()>>,
 }
 
 /// This client has access to the base methods.
@@ -52,6 +61,10 @@ impl BaseClient for AuthCodePkceSpotify {
         Arc::clone(&self.token)
     }
 
+    fn get_refresh_lock(&self) -> Arc<Mutex<()>> {
+        Arc::clone(&self.refresh_lock)
+    }
+
     fn get_creds(&self) -> &Credentials {
         &self.creds
     }
@@ -72,7 +85,11 @@ impl BaseClient for AuthCodePkceSpotify {
                 data.insert(params::CLIENT_ID, &self.creds.id);
 
                 let mut token = self.fetch_access_token(&data, None).await?;
-                token.refresh_token = Some(refresh_token.to_string());
+                // Spotify may rotate the refresh token, in which case the new
+                // one has to be kept, since the previous one may be revoked.
+                token
+                    .refresh_token
+                    .get_or_insert_with(|| refresh_token.to_string());
                 Ok(Some(token))
             }
             _ => Ok(None),
@@ -144,6 +161,7 @@ impl AuthCodePkceSpotify {
         Self {
             creds,
             oauth,
+            http: config.build_http(),
             config,
             ..Default::default()
         }
@@ -184,8 +202,38 @@ impl AuthCodePkceSpotify {
     /// [reference]: https://developer.spotify.com/documentation/general/guides/authorization/code-flow
     /// [rfce]: https://datatracker.ietf.org/doc/html/rfc7636#section-4.1
     pub fn get_authorize_url(&mut self, verifier_bytes: Option<usize>) -> ClientResult<String> {
+        self.get_authorize_url_with_params(verifier_bytes, &[])
+    }
+
+    /// Same as [`Self::get_authorize_url`], but `extra_params` are appended
+    /// to the query string, e.g. the parameters supported by Spotify that
+    /// aren't available here yet.
+    ///
+    /// The parameters set by RSpotify itself, like `state` or
+    /// `code_challenge`, can't be overridden; in that case
+    /// [`ClientError::InvalidInput`] is returned.
+    ///
+    /// [`ClientError::InvalidInput`]: crate::ClientError::InvalidInput
+    pub fn get_authorize_url_with_params(
+        &mut self,
+        verifier_bytes: Option<usize>,
+        extra_params: &[(&str, &str)],
+    ) -> ClientResult<String> {
         log::info!("Building auth URL");
 
+        check_extra_params(
+            extra_params,
+            &[
+                params::CLIENT_ID,
+                params::RESPONSE_TYPE,
+                params::REDIRECT_URI,
+                params::CODE_CHALLENGE_METHOD,
+                params::CODE_CHALLENGE,
+                params::STATE,
+                params::SCOPE,
+            ],
+        )?;
+
         let scopes = join_scopes(&self.oauth.scopes);
         let verifier_bytes = verifier_bytes.unwrap_or(43);
         let (verifier, challenge) = Self::generate_codes(verifier_bytes);
@@ -204,7 +252,11 @@ impl AuthCodePkceSpotify {
         payload.insert(params::STATE, &self.oauth.state);
         payload.insert(params::SCOPE, &scopes);
 
-        let parsed = Url::parse_with_params(auth_urls::AUTHORIZE, payload)?;
+        let params = payload.into_iter().chain(extra_params.iter().copied());
+        let parsed = Url::parse_with_params(
+            &(self.config.auth_prefix.clone() + auth_urls::AUTHORIZE),
+            params,
+        )?;
         Ok(parsed.into())
     }
 }
@@ -0,0 +1,154 @@
+//! Storage backends for the authentication tokens, so that they can be reused
+//! without following the full auth process again.
+
+#[cfg(feature = "cache-encrypted")]
+use crate::ClientError;
+use crate::{model::ModelError, ClientResult, Token};
+
+#[cfg(feature = "cache-encrypted")]
+use std::fs;
+use std::{fmt, io, path::PathBuf};
+
+#[cfg(feature = "cache-encrypted")]
+use chacha20poly1305::{aead::Aead, ChaCha20Poly1305, Key, KeyInit, Nonce};
+#[cfg(feature = "cache-encrypted")]
+use getrandom::getrandom;
+use maybe_async::maybe_async;
+
+/// A storage for the authentication token of a client, used when
+/// [`Config::token_cached`](crate::Config::token_cached) is enabled.
+///
+/// By default, the token is saved as JSON into a file with
+/// [`FileTokenCache`], but it may be replaced with any other implementation in
+/// [`Config::token_cache`](crate::Config#structfield.token_cache), e.g., a database shared
+/// by different instances of a web server.
+#[maybe_async]
+pub trait TokenCache: Send + Sync + fmt::Debug {
+    /// Loads the cached token, or `None` if there isn't one.
+    async fn load(&self) -> ClientResult<Option<Token>>;
+
+    /// Saves the token into the cache, replacing the previous one.
+    async fn save(&self, token: &Token) -> ClientResult<()>;
+}
+
+/// The default token cache, which saves the token as JSON into a file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FileTokenCache {
+    pub path: PathBuf,
+}
+
+impl FileTokenCache {
+    #[must_use]
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self { path: path.into() }
+    }
+}
+
+#[maybe_async]
+impl TokenCache for FileTokenCache {
+    async fn load(&self) -> ClientResult<Option<Token>> {
+        match Token::from_cache(&self.path) {
+            Ok(token) => Ok(Some(token)),
+            Err(ModelError::Io(err)) if err.kind() == io::ErrorKind::NotFound => Ok(None),
+            Err(err) => Err(err.into()),
+        }
+    }
+
+    async fn save(&self, token: &Token) -> ClientResult<()> {
+        token.write_cache(&self.path)?;
+        Ok(())
+    }
+}
+
+/// The length in bytes of the nonces used by [`EncryptedFileTokenCache`].
+#[cfg(feature = "cache-encrypted")]
+const NONCE_LEN: usize = 12;
+
+/// A token cache that saves the token into a file, like [`FileTokenCache`],
+/// but encrypted with ChaCha20-Poly1305 and a user-supplied key. This way, the
+/// refresh token isn't stored in plain text.
+///
+/// The file contains a random nonce followed by the encrypted JSON. Loading it
+/// with the wrong key, or after it's been tampered with, will fail with a
+/// [`ClientError::CacheFile`] error instead of returning a corrupt token.
+///
+/// Note: this requires the `cache-encrypted` feature.
+#[cfg(feature = "cache-encrypted")]
+#[derive(Clone)]
+pub struct EncryptedFileTokenCache {
+    pub path: PathBuf,
+    key: [u8; 32],
+}
+
+#[cfg(feature = "cache-encrypted")]
+impl EncryptedFileTokenCache {
+    #[must_use]
+    pub fn new(path: impl Into<PathBuf>, key: [u8; 32]) -> Self {
+        Self {
+            path: path.into(),
+            key,
+        }
+    }
+
+    fn cipher(&self) -> ChaCha20Poly1305 {
+        ChaCha20Poly1305::new(Key::from_slice(&self.key))
+    }
+}
+
+// The key is left out on purpose, so that it doesn't end up in logs.
+#[cfg(feature = "cache-encrypted")]
+impl fmt::Debug for EncryptedFileTokenCache {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("EncryptedFileTokenCache")
+            .field("path", &self.path)
+            .finish_non_exhaustive()
+    }
+}
+
+#[cfg(feature = "cache-encrypted")]
+#[maybe_async]
+impl TokenCache for EncryptedFileTokenCache {
+    async fn load(&self) -> ClientResult<Option<Token>> {
+        let data = match fs::read(&self.path) {
+            Ok(data) => data,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(None),
+            Err(err) => return Err(err.into()),
+        };
+        if data.len() < NONCE_LEN {
+            return Err(ClientError::CacheFile(
+                "the encrypted token cache is truncated".to_owned(),
+            ));
+        }
+
+        let (nonce, ciphertext) = data.split_at(NONCE_LEN);
+        let json = self
+            .cipher()
+            .decrypt(Nonce::from_slice(nonce), ciphertext)
+            .map_err(|_| {
+                ClientError::CacheFile(
+                    "couldn't decrypt the token cache, the key may be wrong".to_owned(),
+                )
+            })?;
+        let token = serde_json::from_slice(&json)?;
+
+        Ok(Some(token))
+    }
+
+    async fn save(&self, token: &Token) -> ClientResult<()> {
+        let json = serde_json::to_vec(token)?;
+
+        let mut nonce = [0u8; NONCE_LEN];
+        getrandom(&mut nonce)
+            .map_err(|err| ClientError::CacheFile(format!("couldn't generate a nonce: {}", err)))?;
+        let ciphertext = self
+            .cipher()
+            .encrypt(Nonce::from_slice(&nonce), json.as_slice())
+            .map_err(|_| ClientError::CacheFile("couldn't encrypt the token".to_owned()))?;
+
+        let mut data = nonce.to_vec();
+        data.extend(ciphertext);
+        fs::write(&self.path, data)?;
+
+        Ok(())
+    }
+}
@@ -15,6 +15,10 @@ use std::sync::Arc;
 /// client credentials, without user authorization. The only step to take is to
 /// call [`Self::request_token`]. See [this example][example-main].
 ///
+/// There's no refresh token in this flow, but if
+/// [`Config::token_refreshing`] is enabled, a new token is requested in the
+/// same way once the current one expires, right before the next request.
+///
 /// Note: This flow does not include authorization and therefore cannot be used
 /// to access or to manage the endpoints related to user private data in
 /// [`OAuthClient`](crate::clients::OAuthClient).
@@ -27,6 +31,9 @@ pub struct ClientCredsSpotify {
     pub creds: Credentials,
     pub token: Arc<Mutex<Option<Token>>>,
     pub(crate) http: HttpClient,
+    /// Held while the token is being refreshed automatically, so that it's
+    /// only refreshed once at a time.
+    pub(crate) refresh_lock: Arc<Mutex<()>>,
 }
 
 /// This client has access to the base methods.
@@ -40,6 +47,10 @@ impl BaseClient for ClientCredsSpotify {
         Arc::clone(&self.token)
     }
 
+    fn get_refresh_lock(&self) -> Arc<Mutex<()>> {
+        Arc::clone(&self.refresh_lock)
+    }
+
     fn get_creds(&self) -> &Credentials {
         &self.creds
     }
@@ -84,17 +95,19 @@ impl ClientCredsSpotify {
     #[must_use]
     pub fn with_config(creds: Credentials, config: Config) -> Self {
         Self {
+            http: config.build_http(),
             config,
             creds,
             ..Default::default()
         }
     }
 
-    /// Tries to read the cache file's token.
+    /// Tries to read the cached token.
     ///
-    /// This will return an error if the token couldn't be read (e.g. it's not
-    /// available or the JSON is malformed). It may return `Ok(None)` if:
+    /// This will return an error if the token couldn't be read (e.g. the JSON
+    /// is malformed). It may return `Ok(None)` if:
     ///
+    /// * There's no token in the cache
     /// * The read token is expired
     /// * The cached token is disabled in the config
     #[maybe_async]
@@ -105,8 +118,11 @@ impl ClientCredsSpotify {
         }
 
         log::info!("Reading token cache");
-        let token = Token::from_cache(&self.get_config().cache_path)?;
-        if token.is_expired() {
+        let token = match self.get_config().token_cache().load().await? {
+            Some(token) => token,
+            None => return Ok(None),
+        };
+        if token.is_expired_with_skew(self.get_config().token_expiry_skew) {
             // Invalid token, since it's expired.
             Ok(None)
         } else {
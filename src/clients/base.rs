@@ -1,23 +1,41 @@
 use crate::{
     auth_urls,
     clients::{
-        convert_result,
+        convert_http_error, convert_optional_result, convert_result, page_size,
         pagination::{paginate, paginate_with_ctx, Paginator},
+        PAGE_LIMIT, PLAYLIST_ITEMS_PAGE_LIMIT,
     },
     http::{BaseHttpClient, Form, Headers, HttpClient, Query},
-    join_ids,
+    join_ids, join_ids_limited,
     model::*,
     sync::Mutex,
     util::build_map,
-    ClientResult, Config, Credentials, Token,
+    ClientError, ClientResult, Config, Credentials, Token,
 };
 
 use std::{collections::HashMap, fmt, sync::Arc};
 
 use chrono::Utc;
 use maybe_async::maybe_async;
+use serde::de::DeserializeOwned;
 use serde_json::Value;
 
+/// The maximum number of IDs Spotify accepts in a single request to the
+/// endpoints that fetch several tracks, artists, shows or episodes at once.
+const SEVERAL_IDS_LIMIT: usize = 50;
+
+/// The maximum number of IDs Spotify accepts in a single request to the
+/// endpoint that fetches several albums at once.
+const SEVERAL_ALBUMS_LIMIT: usize = 20;
+
+/// Whether a request should be replayed after refreshing the token, because
+/// Spotify considered it unauthorized.
+fn should_replay(config: &Config, result: &ClientResult<String>) -> bool {
+    config.token_refreshing
+        && config.refresh_on_unauthorized
+        && matches!(result, Err(ClientError::Api(err)) if err.status == 401)
+}
+
 /// This trait implements the basic endpoints from the Spotify API that may be
 /// accessed without user authorization, including parts of the authentication
 /// flow that are shared, and the endpoints.
@@ -33,8 +51,15 @@ where
     /// Note that the token is wrapped by a `Mutex` in order to allow interior
     /// mutability. This is required so that the entire client doesn't have to
     /// be mutable (the token is accessed to from every endpoint).
+    ///
+    /// It's also wrapped by an `Arc`, so clones of a client share the same
+    /// token: refreshing it from one of them updates it for all of them.
     fn get_token(&self) -> Arc<Mutex<Option<Token>>>;
 
+    /// The lock held while the token is being refreshed automatically. It
+    /// must be shared by the clones of the client, just like the token.
+    fn get_refresh_lock(&self) -> Arc<Mutex<()>>;
+
     /// If it's a relative URL like "me", the prefix is appended to it.
     /// Otherwise, the same URL is returned.
     fn endpoint_url(&self, url: &str) -> String {
@@ -56,21 +81,35 @@ where
             return Ok(());
         }
 
+        if !self.is_token_expired().await {
+            return Ok(());
+        }
+
+        // Only one refresh is performed at a time. The requests that were
+        // waiting for it check again afterwards, and will find the new token
+        // instead of refreshing it again.
+        let refresh_lock = self.get_refresh_lock();
+        let _guard = refresh_lock.lock().await.unwrap();
+        if self.is_token_expired().await {
+            self.refresh_token().await
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Whether there's a token and it's expired, considering the configured
+    /// [`Config::token_expiry_skew`].
+    #[doc(hidden)]
+    async fn is_token_expired(&self) -> bool {
         // NOTE: It's important to not leave the token locked, or else a
         // deadlock when calling `refresh_token` will occur.
-        let should_reauth = self
-            .get_token()
+        let skew = self.get_config().token_expiry_skew;
+        self.get_token()
             .lock()
             .await
             .unwrap()
             .as_ref()
-            .map_or(false, Token::is_expired);
-
-        if should_reauth {
-            self.refresh_token().await
-        } else {
-            Ok(())
-        }
+            .map_or(false, |token| token.is_expired_with_skew(skew))
     }
 
     /// Refreshes the current access token given a refresh token. The obtained
@@ -81,6 +120,43 @@ where
         self.write_token_cache().await
     }
 
+    /// Refreshes the token after Spotify rejected a request with `401
+    /// Unauthorized`, given the headers it was sent with, and returns whether
+    /// the request should be replayed with the new token. Just like in
+    /// [`Self::auto_reauth`], only one refresh is performed at a time, and if
+    /// another request already replaced the rejected token in the meantime,
+    /// it isn't refreshed again.
+    ///
+    /// If no new token can be obtained, e.g. because there's no refresh
+    /// token, the current one is kept and the request isn't replayed.
+    #[doc(hidden)]
+    async fn refresh_after_unauthorized(&self, rejected_headers: &Headers) -> ClientResult<bool> {
+        let refresh_lock = self.get_refresh_lock();
+        let _guard = refresh_lock.lock().await.unwrap();
+        let current_headers = self
+            .get_token()
+            .lock()
+            .await
+            .unwrap()
+            .as_ref()
+            .map(Token::auth_headers);
+        let is_rejected = current_headers.map_or(true, |headers| {
+            headers.get("authorization") == rejected_headers.get("authorization")
+        });
+        if !is_rejected {
+            return Ok(true);
+        }
+
+        match self.refetch_token().await? {
+            Some(token) => {
+                *self.get_token().lock().await.unwrap() = Some(token);
+                self.write_token_cache().await?;
+                Ok(true)
+            }
+            None => Ok(false),
+        }
+    }
+
     /// The headers required for authenticated requests to the API.
     ///
     /// Since this is accessed by authenticated requests always, it's where the
@@ -105,14 +181,14 @@ where
     //
     // The Spotify client has two different wrappers to perform requests:
     //
-    // * Basic wrappers: `get`, `post`, `put`, `delete`, `post_form`. These only
-    //   append the configured Spotify API URL to the relative URL provided so
-    //   that it's not forgotten. They're used in the authentication process to
-    //   request an access token and similars.
+    // * Basic wrappers: `get`, `post`, `put`, `put_raw`, `delete`, `post_form`.
+    //   These only append the configured Spotify API URL to the relative URL
+    //   provided so that it's not forgotten. They're used in the authentication
+    //   process to request an access token and similars.
     // * Endpoint wrappers: `endpoint_get`, `endpoint_post`, `endpoint_put`,
-    //   `endpoint_delete`. These append the authentication headers for endpoint
-    //   requests to reduce the code needed for endpoints and make them as
-    //   concise as possible.
+    //   `endpoint_put_raw`, `endpoint_delete`. These append the authentication
+    //   headers for endpoint requests to reduce the code needed for endpoints
+    //   and make them as concise as possible.
 
     #[doc(hidden)]
     #[inline]
@@ -123,7 +199,10 @@ where
         payload: &Query<'_>,
     ) -> ClientResult<String> {
         let url = self.endpoint_url(url);
-        Ok(self.get_http().get(&url, headers, payload).await?)
+        match self.get_http().get(&url, headers, payload).await {
+            Ok(response) => Ok(response),
+            Err(err) => Err(convert_http_error(err).await),
+        }
     }
 
     #[doc(hidden)]
@@ -135,7 +214,10 @@ where
         payload: &Value,
     ) -> ClientResult<String> {
         let url = self.endpoint_url(url);
-        Ok(self.get_http().post(&url, headers, payload).await?)
+        match self.get_http().post(&url, headers, payload).await {
+            Ok(response) => Ok(response),
+            Err(err) => Err(convert_http_error(err).await),
+        }
     }
 
     #[doc(hidden)]
@@ -147,7 +229,10 @@ where
         payload: &Form<'_>,
     ) -> ClientResult<String> {
         let url = self.endpoint_url(url);
-        Ok(self.get_http().post_form(&url, headers, payload).await?)
+        match self.get_http().post_form(&url, headers, payload).await {
+            Ok(response) => Ok(response),
+            Err(err) => Err(convert_http_error(err).await),
+        }
     }
 
     #[doc(hidden)]
@@ -159,7 +244,25 @@ where
         payload: &Value,
     ) -> ClientResult<String> {
         let url = self.endpoint_url(url);
-        Ok(self.get_http().put(&url, headers, payload).await?)
+        match self.get_http().put(&url, headers, payload).await {
+            Ok(response) => Ok(response),
+            Err(err) => Err(convert_http_error(err).await),
+        }
+    }
+
+    #[doc(hidden)]
+    #[inline]
+    async fn put_raw(
+        &self,
+        url: &str,
+        headers: Option<&Headers>,
+        payload: &str,
+    ) -> ClientResult<String> {
+        let url = self.endpoint_url(url);
+        match self.get_http().put_raw(&url, headers, payload).await {
+            Ok(response) => Ok(response),
+            Err(err) => Err(convert_http_error(err).await),
+        }
     }
 
     #[doc(hidden)]
@@ -171,41 +274,98 @@ where
         payload: &Value,
     ) -> ClientResult<String> {
         let url = self.endpoint_url(url);
-        Ok(self.get_http().delete(&url, headers, payload).await?)
+        match self.get_http().delete(&url, headers, payload).await {
+            Ok(response) => Ok(response),
+            Err(err) => Err(convert_http_error(err).await),
+        }
     }
 
     // The wrappers for the endpoints, which also includes the required
-    // autentication.
+    // autentication. If Spotify rejects the token before it was expected to
+    // expire, the request is replayed once after refreshing it.
 
     #[doc(hidden)]
     #[inline]
     async fn endpoint_get(&self, url: &str, payload: &Query<'_>) -> ClientResult<String> {
         let headers = self.auth_headers().await;
-        self.get(url, Some(&headers), payload).await
+        let result = self.get(url, Some(&headers), payload).await;
+        if should_replay(self.get_config(), &result)
+            && self.refresh_after_unauthorized(&headers).await?
+        {
+            let headers = self.auth_headers().await;
+            return self.get(url, Some(&headers), payload).await;
+        }
+        result
     }
 
     #[doc(hidden)]
     #[inline]
     async fn endpoint_post(&self, url: &str, payload: &Value) -> ClientResult<String> {
         let headers = self.auth_headers().await;
-        self.post(url, Some(&headers), payload).await
+        let result = self.post(url, Some(&headers), payload).await;
+        if should_replay(self.get_config(), &result)
+            && self.refresh_after_unauthorized(&headers).await?
+        {
+            let headers = self.auth_headers().await;
+            return self.post(url, Some(&headers), payload).await;
+        }
+        result
     }
 
     #[doc(hidden)]
     #[inline]
     async fn endpoint_put(&self, url: &str, payload: &Value) -> ClientResult<String> {
         let headers = self.auth_headers().await;
-        self.put(url, Some(&headers), payload).await
+        let result = self.put(url, Some(&headers), payload).await;
+        if should_replay(self.get_config(), &result)
+            && self.refresh_after_unauthorized(&headers).await?
+        {
+            let headers = self.auth_headers().await;
+            return self.put(url, Some(&headers), payload).await;
+        }
+        result
+    }
+
+    #[doc(hidden)]
+    #[inline]
+    async fn endpoint_put_raw(
+        &self,
+        url: &str,
+        content_type: &str,
+        payload: &str,
+    ) -> ClientResult<String> {
+        let raw_headers = |mut headers: Headers| {
+            headers.insert("Content-Type".to_owned(), content_type.to_owned());
+            headers
+        };
+
+        let headers = raw_headers(self.auth_headers().await);
+        let result = self.put_raw(url, Some(&headers), payload).await;
+        if should_replay(self.get_config(), &result)
+            && self.refresh_after_unauthorized(&headers).await?
+        {
+            let headers = raw_headers(self.auth_headers().await);
+            return self.put_raw(url, Some(&headers), payload).await;
+        }
+        result
     }
 
     #[doc(hidden)]
     #[inline]
     async fn endpoint_delete(&self, url: &str, payload: &Value) -> ClientResult<String> {
         let headers = self.auth_headers().await;
-        self.delete(url, Some(&headers), payload).await
+        let result = self.delete(url, Some(&headers), payload).await;
+        if should_replay(self.get_config(), &result)
+            && self.refresh_after_unauthorized(&headers).await?
+        {
+            let headers = self.auth_headers().await;
+            return self.delete(url, Some(&headers), payload).await;
+        }
+        result
     }
 
-    /// Updates the cache file at the internal cache path.
+    /// Updates the token cache, which is the cache file at the internal cache
+    /// path unless a custom one is configured.
     ///
     /// This should be used whenever it's possible to, even if the cached token
     /// isn't configured, because this will already check `Config::token_cached`
@@ -217,8 +377,9 @@ where
         }
 
         log::info!("Writing token cache");
-        if let Some(tok) = self.get_token().lock().await.unwrap().as_ref() {
-            tok.write_cache(&self.get_config().cache_path)?;
+        let token = self.get_token().lock().await.unwrap().clone();
+        if let Some(tok) = token {
+            self.get_config().token_cache().save(&tok).await?;
         }
 
         Ok(())
@@ -230,7 +391,8 @@ where
         payload: &Form<'_>,
         headers: Option<&Headers>,
     ) -> ClientResult<Token> {
-        let response = self.post_form(auth_urls::TOKEN, headers, payload).await?;
+        let auth_url = self.get_config().auth_prefix.clone() + auth_urls::TOKEN;
+        let response = self.post_form(&auth_url, headers, payload).await?;
 
         let mut tok = serde_json::from_str::<Token>(&response)?;
         tok.expires_at = Utc::now().checked_add_signed(tok.expires_in);
@@ -241,18 +403,27 @@ where
     ///
     /// Parameters:
     /// - track_id - a spotify URI, URL or ID
+    /// - market - an ISO 3166-1 alpha-2 country code or the string from_token.
     ///
     /// [Reference](https://developer.spotify.com/documentation/web-api/reference/#/operations/get-track)
-    async fn track(&self, track_id: TrackId<'_>) -> ClientResult<FullTrack> {
+    async fn track(
+        &self,
+        track_id: TrackId<'_>,
+        market: Option<Market>,
+    ) -> ClientResult<FullTrack> {
+        let params = build_map([("market", market.map(Into::into))]);
+
         let url = format!("tracks/{}", track_id.id());
-        let result = self.endpoint_get(&url, &Query::new()).await?;
+        let result = self.endpoint_get(&url, &params).await?;
         convert_result(&result)
     }
 
     /// Returns a list of tracks given a list of track IDs, URIs, or URLs.
     ///
     /// Parameters:
-    /// - track_ids - a list of spotify URIs, URLs or IDs
+    /// - track_ids - a list of spotify URIs, URLs or IDs. Spotify accepts up to
+    ///   50 IDs per request, so more than that are fetched in multiple
+    ///   requests.
     /// - market - an ISO 3166-1 alpha-2 country code or the string from_token.
     ///
     /// [Reference](https://developer.spotify.com/documentation/web-api/reference/#/operations/get-several-tracks)
@@ -261,12 +432,17 @@ where
         track_ids: impl IntoIterator<Item = TrackId<'a>> + Send + 'a,
         market: Option<Market>,
     ) -> ClientResult<Vec<FullTrack>> {
-        let ids = join_ids(track_ids);
+        let track_ids = track_ids.into_iter().collect::<Vec<_>>();
         let params = build_map([("market", market.map(Into::into))]);
 
-        let url = format!("tracks/?ids={ids}");
-        let result = self.endpoint_get(&url, &params).await?;
-        convert_result::<FullTracks>(&result).map(|x| x.tracks)
+        let mut tracks = Vec::with_capacity(track_ids.len());
+        for chunk in track_ids.chunks(SEVERAL_IDS_LIMIT) {
+            let ids = join_ids(chunk.iter().map(TrackId::as_ref));
+            let url = format!("tracks/?ids={ids}");
+            let result = self.endpoint_get(&url, &params).await?;
+            tracks.extend(convert_result::<FullTracks>(&result)?.tracks);
+        }
+        Ok(tracks)
     }
 
     /// Returns a single artist given the artist's ID, URI or URL.
@@ -284,25 +460,34 @@ where
     /// Returns a list of artists given the artist IDs, URIs, or URLs.
     ///
     /// Parameters:
-    /// - artist_ids - a list of artist IDs, URIs or URLs
+    /// - artist_ids - a list of artist IDs, URIs or URLs. Spotify accepts up to
+    ///   50 IDs per request, so more than that are fetched in multiple
+    ///   requests.
     ///
     /// [Reference](https://developer.spotify.com/documentation/web-api/reference/#/operations/get-multiple-artists)
     async fn artists<'a>(
         &self,
         artist_ids: impl IntoIterator<Item = ArtistId<'a>> + Send + 'a,
     ) -> ClientResult<Vec<FullArtist>> {
-        let ids = join_ids(artist_ids);
-        let url = format!("artists/?ids={ids}");
-        let result = self.endpoint_get(&url, &Query::new()).await?;
-
-        convert_result::<FullArtists>(&result).map(|x| x.artists)
+        let artist_ids = artist_ids.into_iter().collect::<Vec<_>>();
+
+        let mut artists = Vec::with_capacity(artist_ids.len());
+        for chunk in artist_ids.chunks(SEVERAL_IDS_LIMIT) {
+            let ids = join_ids(chunk.iter().map(ArtistId::as_ref));
+            let url = format!("artists/?ids={ids}");
+            let result = self.endpoint_get(&url, &Query::new()).await?;
+            artists.extend(convert_result::<FullArtists>(&result)?.artists);
+        }
+        Ok(artists)
     }
 
     /// Get Spotify catalog information about an artist's albums.
     ///
     /// Parameters:
     /// - artist_id - the artist ID, URI or URL
-    /// - album_type - 'album', 'single', 'appears_on', 'compilation'
+    /// - include_groups - the album groups to return, out of `album`,
+    ///   `single`, `appears_on` and `compilation`. All of them are returned
+    ///   if it's empty. Each group may only be given once.
     /// - market - limit the response to one particular country.
     /// - limit  - the number of albums to return
     /// - offset - the index of the first album to return
@@ -314,7 +499,7 @@ where
     fn artist_albums<'a>(
         &'a self,
         artist_id: ArtistId<'a>,
-        album_type: Option<AlbumType>,
+        include_groups: &'a [AlbumType],
         market: Option<Market>,
     ) -> Paginator<'_, ClientResult<SimplifiedAlbum>> {
         paginate_with_ctx(
@@ -322,13 +507,13 @@ where
             move |(slf, artist_id), limit, offset| {
                 slf.artist_albums_manual(
                     artist_id.as_ref(),
-                    album_type,
+                    include_groups,
                     market,
                     Some(limit),
                     Some(offset),
                 )
             },
-            self.get_config().pagination_chunks,
+            page_size(self.get_config(), PAGE_LIMIT),
         )
     }
 
@@ -336,15 +521,32 @@ where
     async fn artist_albums_manual(
         &self,
         artist_id: ArtistId<'_>,
-        album_type: Option<AlbumType>,
+        include_groups: &[AlbumType],
         market: Option<Market>,
         limit: Option<u32>,
         offset: Option<u32>,
     ) -> ClientResult<Page<SimplifiedAlbum>> {
+        let mut groups = Vec::with_capacity(include_groups.len());
+        for group in include_groups {
+            if matches!(group, AlbumType::Unknown(_)) {
+                return Err(ClientError::InvalidInput(
+                    "unknown album groups can't be requested".to_owned(),
+                ));
+            }
+            let group: &str = group.into();
+            if groups.contains(&group) {
+                return Err(ClientError::InvalidInput(format!(
+                    "the album group `{group}` was given more than once"
+                )));
+            }
+            groups.push(group);
+        }
+        let include_groups = (!groups.is_empty()).then(|| groups.join(","));
+
         let limit = limit.map(|x| x.to_string());
         let offset = offset.map(|x| x.to_string());
         let params = build_map([
-            ("album_type", album_type.map(Into::into)),
+            ("include_groups", include_groups.as_deref()),
             ("market", market.map(Into::into)),
             ("limit", limit.as_deref()),
             ("offset", offset.as_deref()),
@@ -396,29 +598,46 @@ where
     ///
     /// Parameters:
     /// - album_id - the album ID, URI or URL
+    /// - market - an ISO 3166-1 alpha-2 country code or the string from_token.
     ///
     /// [Reference](https://developer.spotify.com/documentation/web-api/reference/#/operations/get-an-album)
-    async fn album(&self, album_id: AlbumId<'_>) -> ClientResult<FullAlbum> {
-        let url = format!("albums/{}", album_id.id());
+    async fn album(
+        &self,
+        album_id: AlbumId<'_>,
+        market: Option<Market>,
+    ) -> ClientResult<FullAlbum> {
+        let params = build_map([("market", market.map(Into::into))]);
 
-        let result = self.endpoint_get(&url, &Query::new()).await?;
+        let url = format!("albums/{}", album_id.id());
+        let result = self.endpoint_get(&url, &params).await?;
         convert_result(&result)
     }
 
     /// Returns a list of albums given the album IDs, URIs, or URLs.
     ///
     /// Parameters:
-    /// - albums_ids - a list of album IDs, URIs or URLs
+    /// - albums_ids - a list of album IDs, URIs or URLs. Spotify accepts up to
+    ///   20 IDs per request, so more than that are fetched in multiple
+    ///   requests.
+    /// - market - an ISO 3166-1 alpha-2 country code or the string from_token.
     ///
     /// [Reference](https://developer.spotify.com/documentation/web-api/reference/#/operations/get-multiple-albums)
     async fn albums<'a>(
         &self,
         album_ids: impl IntoIterator<Item = AlbumId<'a>> + Send + 'a,
+        market: Option<Market>,
     ) -> ClientResult<Vec<FullAlbum>> {
-        let ids = join_ids(album_ids);
-        let url = format!("albums/?ids={ids}");
-        let result = self.endpoint_get(&url, &Query::new()).await?;
-        convert_result::<FullAlbums>(&result).map(|x| x.albums)
+        let album_ids = album_ids.into_iter().collect::<Vec<_>>();
+        let params = build_map([("market", market.map(Into::into))]);
+
+        let mut albums = Vec::with_capacity(album_ids.len());
+        for chunk in album_ids.chunks(SEVERAL_ALBUMS_LIMIT) {
+            let ids = join_ids(chunk.iter().map(AlbumId::as_ref));
+            let url = format!("albums/?ids={ids}");
+            let result = self.endpoint_get(&url, &params).await?;
+            albums.extend(convert_result::<FullAlbums>(&result)?.albums);
+        }
+        Ok(albums)
     }
 
     /// Search for an Item. Get Spotify catalog information about artists,
@@ -460,27 +679,77 @@ where
         convert_result(&result)
     }
 
+    /// Search for multiple kinds of items at once. Same as [`Self::search`],
+    /// but every requested type will have its own page in the result.
+    ///
+    /// Parameters:
+    /// - q - the search query
+    /// - types - the types of item to return. Any of 'artist', 'album',
+    ///   'track', 'playlist', 'show' or 'episode'
+    /// - market - An ISO 3166-1 alpha-2 country code or the string from_token.
+    /// - include_external: Optional.Possible values: audio. If
+    ///   include_external=audio is specified the response will include any
+    ///   relevant audio content that is hosted externally.
+    /// - limit  - the number of items to return for each type
+    /// - offset - the index of the first item to return for each type
+    ///
+    /// [Reference](https://developer.spotify.com/documentation/web-api/reference/#/operations/search)
+    async fn search_multi<'a>(
+        &self,
+        q: &str,
+        types: impl IntoIterator<Item = SearchType> + Send + 'a,
+        market: Option<Market>,
+        include_external: Option<IncludeExternal>,
+        limit: Option<u32>,
+        offset: Option<u32>,
+    ) -> ClientResult<SearchResults> {
+        let types = types
+            .into_iter()
+            .map(Into::into)
+            .collect::<Vec<&'static str>>()
+            .join(",");
+        let limit = limit.map(|s| s.to_string());
+        let offset = offset.map(|s| s.to_string());
+        let params = build_map([
+            ("q", Some(q)),
+            ("type", Some(&types)),
+            ("market", market.map(Into::into)),
+            ("include_external", include_external.map(Into::into)),
+            ("limit", limit.as_deref()),
+            ("offset", offset.as_deref()),
+        ]);
+
+        let result = self.endpoint_get("search", &params).await?;
+        convert_result(&result)
+    }
+
     /// Get Spotify catalog information about an album's tracks.
     ///
     /// Parameters:
     /// - album_id - the album ID, URI or URL
+    /// - market - an ISO 3166-1 alpha-2 country code or the string from_token,
+    ///   to apply track relinking
     /// - limit  - the number of items to return
     /// - offset - the index of the first item to return
     ///
     /// See [`Self::album_track_manual`] for a manually paginated version of
-    /// this.
+    /// this. To fetch the tracks of many albums at once, note that the albums
+    /// returned by [`Self::albums`] already include the first page of their
+    /// tracks, so this is only needed for the albums with more tracks than
+    /// that.
     ///
     /// [Reference](https://developer.spotify.com/documentation/web-api/reference/#/operations/get-an-albums-tracks)
     fn album_track<'a>(
         &'a self,
         album_id: AlbumId<'a>,
+        market: Option<Market>,
     ) -> Paginator<'_, ClientResult<SimplifiedTrack>> {
         paginate_with_ctx(
             (self, album_id),
             move |(slf, album_id), limit, offset| {
-                slf.album_track_manual(album_id.as_ref(), Some(limit), Some(offset))
+                slf.album_track_manual(album_id.as_ref(), market, Some(limit), Some(offset))
             },
-            self.get_config().pagination_chunks,
+            page_size(self.get_config(), PAGE_LIMIT),
         )
     }
 
@@ -488,12 +757,17 @@ where
     async fn album_track_manual(
         &self,
         album_id: AlbumId<'_>,
+        market: Option<Market>,
         limit: Option<u32>,
         offset: Option<u32>,
     ) -> ClientResult<Page<SimplifiedTrack>> {
         let limit = limit.map(|s| s.to_string());
         let offset = offset.map(|s| s.to_string());
-        let params = build_map([("limit", limit.as_deref()), ("offset", offset.as_deref())]);
+        let params = build_map([
+            ("market", market.map(Into::into)),
+            ("limit", limit.as_deref()),
+            ("offset", offset.as_deref()),
+        ]);
 
         let url = format!("albums/{}/tracks", album_id.id());
         let result = self.endpoint_get(&url, &params).await?;
@@ -532,6 +806,18 @@ where
         convert_result(&result)
     }
 
+    /// Get the current image associated with a specific playlist.
+    ///
+    /// Parameters:
+    /// - playlist_id - the id of the playlist
+    ///
+    /// [Reference](https://developer.spotify.com/documentation/web-api/reference/#/operations/get-playlist-cover)
+    async fn playlist_cover_image(&self, playlist_id: PlaylistId<'_>) -> ClientResult<Vec<Image>> {
+        let url = format!("playlists/{}/images", playlist_id.id());
+        let result = self.endpoint_get(&url, &Query::new()).await?;
+        convert_result(&result)
+    }
+
     /// Gets playlist of a user.
     ///
     /// Parameters:
@@ -564,19 +850,15 @@ where
     ///   follow the playlist. Maximum: 5 ids.
     ///
     /// [Reference](https://developer.spotify.com/documentation/web-api/reference/#/operations/check-if-user-follows-playlist)
-    async fn playlist_check_follow(
+    async fn playlist_check_follow<'a>(
         &self,
         playlist_id: PlaylistId<'_>,
-        user_ids: &[UserId<'_>],
+        user_ids: impl IntoIterator<Item = UserId<'a>> + Send + 'a,
     ) -> ClientResult<Vec<bool>> {
-        debug_assert!(
-            user_ids.len() <= 5,
-            "The maximum length of user ids is limited to 5 :-)"
-        );
         let url = format!(
             "playlists/{}/followers/contains?ids={}",
             playlist_id.id(),
-            user_ids.iter().map(Id::id).collect::<Vec<_>>().join(","),
+            join_ids_limited(user_ids, 5)?,
         );
         let result = self.endpoint_get(&url, &Query::new()).await?;
         convert_result(&result)
@@ -603,7 +885,9 @@ where
     /// Spotify IDs.
     ///
     /// Query Parameters
-    /// - ids(Required) A comma-separated list of the Spotify IDs for the shows. Maximum: 50 IDs.
+    /// - ids(Required) A comma-separated list of the Spotify IDs for the shows.
+    ///   Spotify accepts up to 50 IDs per request, so more than that are
+    ///   fetched in multiple requests.
     /// - market(Optional) An ISO 3166-1 alpha-2 country code or the string from_token.
     ///
     /// [Reference](https://developer.spotify.com/documentation/web-api/reference/#/operations/get-multiple-shows)
@@ -612,11 +896,16 @@ where
         ids: impl IntoIterator<Item = ShowId<'a>> + Send + 'a,
         market: Option<Market>,
     ) -> ClientResult<Vec<SimplifiedShow>> {
-        let ids = join_ids(ids);
-        let params = build_map([("ids", Some(&ids)), ("market", market.map(Into::into))]);
-
-        let result = self.endpoint_get("shows", &params).await?;
-        convert_result::<SeversalSimplifiedShows>(&result).map(|x| x.shows)
+        let ids = ids.into_iter().collect::<Vec<_>>();
+        let mut shows = Vec::with_capacity(ids.len());
+        for chunk in ids.chunks(SEVERAL_IDS_LIMIT) {
+            let ids = join_ids(chunk.iter().map(ShowId::as_ref));
+            let params = build_map([("ids", Some(&ids)), ("market", market.map(Into::into))]);
+
+            let result = self.endpoint_get("shows", &params).await?;
+            shows.extend(convert_result::<SeversalSimplifiedShows>(&result)?.shows);
+        }
+        Ok(shows)
     }
 
     /// Get Spotify catalog information about an show’s episodes. Optional
@@ -644,7 +933,7 @@ where
             move |(slf, id), limit, offset| {
                 slf.get_shows_episodes_manual(id.as_ref(), market, Some(limit), Some(offset))
             },
-            self.get_config().pagination_chunks,
+            page_size(self.get_config(), PAGE_LIMIT),
         )
     }
 
@@ -693,7 +982,9 @@ where
     /// Get Spotify catalog information for multiple episodes based on their Spotify IDs.
     ///
     /// Query Parameters
-    /// - ids: Required. A comma-separated list of the Spotify IDs for the episodes. Maximum: 50 IDs.
+    /// - ids: Required. A comma-separated list of the Spotify IDs for the
+    ///   episodes. Spotify accepts up to 50 IDs per request, so more than that
+    ///   are fetched in multiple requests.
     /// - market: Optional. An ISO 3166-1 alpha-2 country code or the string from_token.
     ///
     /// [Reference](https://developer.spotify.com/documentation/web-api/reference/#/operations/get-multiple-episodes)
@@ -702,11 +993,107 @@ where
         ids: impl IntoIterator<Item = EpisodeId<'a>> + Send + 'a,
         market: Option<Market>,
     ) -> ClientResult<Vec<FullEpisode>> {
-        let ids = join_ids(ids);
+        let ids = ids.into_iter().collect::<Vec<_>>();
+        let mut episodes = Vec::with_capacity(ids.len());
+        for chunk in ids.chunks(SEVERAL_IDS_LIMIT) {
+            let ids = join_ids(chunk.iter().map(EpisodeId::as_ref));
+            let params = build_map([("ids", Some(&ids)), ("market", market.map(Into::into))]);
+
+            let result = self.endpoint_get("episodes", &params).await?;
+            episodes.extend(convert_result::<EpisodesPayload>(&result)?.episodes);
+        }
+        Ok(episodes)
+    }
+
+    /// Get Spotify catalog information for a single audiobook identified by
+    /// its unique Spotify ID.
+    ///
+    /// Path Parameters:
+    /// - id: The Spotify ID for the audiobook.
+    ///
+    /// Query Parameters
+    /// - market(Optional): An ISO 3166-1 alpha-2 country code or the string from_token.
+    ///
+    /// [Reference](https://developer.spotify.com/documentation/web-api/reference/#/operations/get-an-audiobook)
+    async fn get_audiobook(
+        &self,
+        id: AudiobookId<'_>,
+        market: Option<Market>,
+    ) -> ClientResult<Audiobook> {
+        let params = build_map([("market", market.map(Into::into))]);
+
+        let url = format!("audiobooks/{}", id.id());
+        let result = self.endpoint_get(&url, &params).await?;
+        convert_result(&result)
+    }
+
+    /// Get Spotify catalog information for multiple audiobooks based on their
+    /// Spotify IDs.
+    ///
+    /// Query Parameters
+    /// - ids(Required) A comma-separated list of the Spotify IDs for the audiobooks. Maximum: 50 IDs.
+    /// - market(Optional) An ISO 3166-1 alpha-2 country code or the string from_token.
+    ///
+    /// [Reference](https://developer.spotify.com/documentation/web-api/reference/#/operations/get-multiple-audiobooks)
+    async fn get_audiobooks<'a>(
+        &self,
+        ids: impl IntoIterator<Item = AudiobookId<'a>> + Send + 'a,
+        market: Option<Market>,
+    ) -> ClientResult<Vec<Audiobook>> {
+        let ids = join_ids_limited(ids, 50)?;
         let params = build_map([("ids", Some(&ids)), ("market", market.map(Into::into))]);
 
-        let result = self.endpoint_get("episodes", &params).await?;
-        convert_result::<EpisodesPayload>(&result).map(|x| x.episodes)
+        let result = self.endpoint_get("audiobooks", &params).await?;
+        convert_result::<AudiobooksPayload>(&result).map(|x| x.audiobooks)
+    }
+
+    /// Get Spotify catalog information about an audiobook's chapters.
+    ///
+    /// Path Parameters
+    /// - id: The Spotify ID for the audiobook.
+    ///
+    /// Query Parameters
+    /// - limit: Optional. The maximum number of chapters to return. Default: 20. Minimum: 1. Maximum: 50.
+    /// - offset: Optional. The index of the first chapter to return. Default: 0 (the first object). Use with limit to get the next set of chapters.
+    /// - market: Optional. An ISO 3166-1 alpha-2 country code or the string from_token.
+    ///
+    /// See [`Self::get_audiobook_chapters_manual`] for a manually paginated
+    /// version of this.
+    ///
+    /// [Reference](https://developer.spotify.com/documentation/web-api/reference/#/operations/get-audiobook-chapters)
+    fn get_audiobook_chapters<'a>(
+        &'a self,
+        id: AudiobookId<'a>,
+        market: Option<Market>,
+    ) -> Paginator<'_, ClientResult<SimplifiedChapter>> {
+        paginate_with_ctx(
+            (self, id),
+            move |(slf, id), limit, offset| {
+                slf.get_audiobook_chapters_manual(id.as_ref(), market, Some(limit), Some(offset))
+            },
+            page_size(self.get_config(), PAGE_LIMIT),
+        )
+    }
+
+    /// The manually paginated version of [`Self::get_audiobook_chapters`].
+    async fn get_audiobook_chapters_manual(
+        &self,
+        id: AudiobookId<'_>,
+        market: Option<Market>,
+        limit: Option<u32>,
+        offset: Option<u32>,
+    ) -> ClientResult<Page<SimplifiedChapter>> {
+        let limit = limit.map(|x| x.to_string());
+        let offset = offset.map(|x| x.to_string());
+        let params = build_map([
+            ("market", market.map(Into::into)),
+            ("limit", limit.as_deref()),
+            ("offset", offset.as_deref()),
+        ]);
+
+        let url = format!("audiobooks/{}/chapters", id.id());
+        let result = self.endpoint_get(&url, &params).await?;
+        convert_result(&result)
     }
 
     /// Get audio features for a track
@@ -731,15 +1118,11 @@ where
         &self,
         track_ids: impl IntoIterator<Item = TrackId<'a>> + Send + 'a,
     ) -> ClientResult<Option<Vec<AudioFeatures>>> {
-        let url = format!("audio-features/?ids={}", join_ids(track_ids));
+        let url = format!("audio-features/?ids={}", join_ids_limited(track_ids, 100)?);
 
         let result = self.endpoint_get(&url, &Query::new()).await?;
-        if result.is_empty() {
-            Ok(None)
-        } else {
-            convert_result::<Option<AudioFeaturesPayload>>(&result)
-                .map(|option_payload| option_payload.map(|x| x.audio_features))
-        }
+        convert_optional_result::<AudioFeaturesPayload>(&result)
+            .map(|payload| payload.map(|x| x.audio_features))
     }
 
     /// Get Audio Analysis for a Track
@@ -776,7 +1159,7 @@ where
     ) -> Paginator<'_, ClientResult<Category>> {
         paginate(
             move |limit, offset| self.categories_manual(locale, country, Some(limit), Some(offset)),
-            self.get_config().pagination_chunks,
+            page_size(self.get_config(), PAGE_LIMIT),
         )
     }
 
@@ -823,7 +1206,7 @@ where
             move |limit, offset| {
                 self.category_playlists_manual(category_id, country, Some(limit), Some(offset))
             },
-            self.get_config().pagination_chunks,
+            page_size(self.get_config(), PAGE_LIMIT),
         )
     }
 
@@ -909,7 +1292,7 @@ where
     ) -> Paginator<'_, ClientResult<SimplifiedAlbum>> {
         paginate(
             move |limit, offset| self.new_releases_manual(country, Some(limit), Some(offset)),
-            self.get_config().pagination_chunks,
+            page_size(self.get_config(), PAGE_LIMIT),
         )
     }
 
@@ -948,6 +1331,10 @@ where
     ///   in the documentation, these values provide filters and targeting on
     ///   results.
     ///
+    /// Between 1 and 5 seeds must be given in total, counting artists, genres
+    /// and tracks, or [`ClientError::InvalidInput`] is returned without
+    /// performing the request.
+    ///
     /// [Reference](https://developer.spotify.com/documentation/web-api/reference/#/operations/get-recommendations)
     async fn recommendations<'a>(
         &self,
@@ -958,8 +1345,20 @@ where
         market: Option<Market>,
         limit: Option<u32>,
     ) -> ClientResult<Recommendations> {
+        let seed_artists = seed_artists.map(|x| x.into_iter().collect::<Vec<_>>());
+        let seed_genres = seed_genres.map(|x| x.into_iter().collect::<Vec<_>>());
+        let seed_tracks = seed_tracks.map(|x| x.into_iter().collect::<Vec<_>>());
+        let num_seeds = seed_artists.as_ref().map_or(0, Vec::len)
+            + seed_genres.as_ref().map_or(0, Vec::len)
+            + seed_tracks.as_ref().map_or(0, Vec::len);
+        if !(1..=5).contains(&num_seeds) {
+            return Err(ClientError::InvalidInput(format!(
+                "between 1 and 5 seeds are required, but {num_seeds} were given"
+            )));
+        }
+
         let seed_artists = seed_artists.map(join_ids);
-        let seed_genres = seed_genres.map(|x| x.into_iter().collect::<Vec<_>>().join(","));
+        let seed_genres = seed_genres.map(|x| x.join(","));
         let seed_tracks = seed_tracks.map(join_ids);
         let limit = limit.map(|x| x.to_string());
         let mut params = build_map([
@@ -987,6 +1386,25 @@ where
         convert_result(&result)
     }
 
+    /// Get the list of genres that may be used as seeds for
+    /// [`Self::recommendations`].
+    ///
+    /// [Reference](https://developer.spotify.com/documentation/web-api/reference/#/operations/get-recommendation-genres)
+    async fn recommendations_available_genre_seeds(&self) -> ClientResult<Vec<String>> {
+        let result = self
+            .endpoint_get("recommendations/available-genre-seeds", &Query::new())
+            .await?;
+        convert_result::<GenreSeedsPayload>(&result).map(|x| x.genres)
+    }
+
+    /// Get the list of markets where Spotify is available.
+    ///
+    /// [Reference](https://developer.spotify.com/documentation/web-api/reference/#/operations/get-available-markets)
+    async fn available_markets(&self) -> ClientResult<Vec<Country>> {
+        let result = self.endpoint_get("markets", &Query::new()).await?;
+        convert_result::<MarketsPayload>(&result).map(|x| x.markets)
+    }
+
     /// Get full details of the items of a playlist owned by a user.
     ///
     /// Parameters:
@@ -1017,7 +1435,7 @@ where
                     Some(offset),
                 )
             },
-            self.get_config().pagination_chunks,
+            page_size(self.get_config(), PLAYLIST_ITEMS_PAGE_LIMIT),
         )
     }
 
@@ -1030,6 +1448,28 @@ where
         limit: Option<u32>,
         offset: Option<u32>,
     ) -> ClientResult<Page<PlaylistItem>> {
+        let result = self
+            .playlist_items_raw(playlist_id, fields, market, limit, offset)
+            .await?;
+        convert_result(&result)
+    }
+
+    /// The same as [`Self::playlist_items_manual`], but returns the JSON
+    /// response as is instead of deserializing it into a [`Page`].
+    ///
+    /// This is useful for bulk exports of large playlists, where only a few
+    /// fields are needed: they can be extracted from the raw response with
+    /// `serde_json` (e.g. borrowing from it, with a custom struct) without
+    /// allocating all the items. Consider also limiting the response with the
+    /// `fields` parameter.
+    async fn playlist_items_raw(
+        &self,
+        playlist_id: PlaylistId<'_>,
+        fields: Option<&str>,
+        market: Option<Market>,
+        limit: Option<u32>,
+        offset: Option<u32>,
+    ) -> ClientResult<String> {
         let limit = limit.map(|s| s.to_string());
         let offset = offset.map(|s| s.to_string());
         let params = build_map([
@@ -1040,8 +1480,7 @@ where
         ]);
 
         let url = format!("playlists/{}/tracks", playlist_id.id());
-        let result = self.endpoint_get(&url, &params).await?;
-        convert_result(&result)
+        self.endpoint_get(&url, &params).await
     }
 
     /// Gets playlists of a user.
@@ -1064,7 +1503,7 @@ where
             move |(slf, user_id), limit, offset| {
                 slf.user_playlists_manual(user_id.as_ref(), Some(limit), Some(offset))
             },
-            self.get_config().pagination_chunks,
+            page_size(self.get_config(), PAGE_LIMIT),
         )
     }
 
@@ -1083,4 +1522,59 @@ where
         let result = self.endpoint_get(&url, &params).await?;
         convert_result(&result)
     }
+
+    /// Fetches the object a Spotify URI (e.g.
+    /// `spotify:track:4y4VO05kYgUTo2bzbox1an`) or URL (e.g.
+    /// `https://open.spotify.com/track/4y4VO05kYgUTo2bzbox1an`) points to,
+    /// with the endpoint for its type. This fails with
+    /// [`ClientError::InvalidId`] if it's invalid or its type isn't supported.
+    ///
+    /// Parameters:
+    /// - uri - the URI or URL of the object
+    /// - market - an ISO 3166-1 alpha-2 country code or the string from_token.
+    ///   It's ignored for artists and users.
+    async fn resolve_uri(&self, uri: &str, market: Option<Market>) -> ClientResult<ResolvedItem> {
+        let (tpe, id) = if uri.starts_with("http") {
+            parse_url(uri)?
+        } else {
+            parse_uri(uri)?
+        };
+        let item = match tpe {
+            Type::Track => ResolvedItem::Track(self.track(TrackId::from_id(id)?, market).await?),
+            Type::Album => ResolvedItem::Album(self.album(AlbumId::from_id(id)?, market).await?),
+            Type::Artist => ResolvedItem::Artist(self.artist(ArtistId::from_id(id)?).await?),
+            Type::Playlist => ResolvedItem::Playlist(
+                self.playlist(PlaylistId::from_id(id)?, None, market)
+                    .await?,
+            ),
+            Type::Show => ResolvedItem::Show(self.get_a_show(ShowId::from_id(id)?, market).await?),
+            Type::Episode => {
+                ResolvedItem::Episode(self.get_an_episode(EpisodeId::from_id(id)?, market).await?)
+            }
+            Type::Audiobook => ResolvedItem::Audiobook(
+                self.get_audiobook(AudiobookId::from_id(id)?, market)
+                    .await?,
+            ),
+            Type::User => ResolvedItem::User(self.user(UserId::from_id(id)?).await?),
+            _ => return Err(IdError::InvalidType.into()),
+        };
+        Ok(item)
+    }
+
+    /// Fetches the page after the given one by following its `next` URL, or
+    /// returns `None` if it's the last one. This is useful to resume the
+    /// pagination manually, e.g. from a page obtained with any of the `_manual`
+    /// endpoints.
+    async fn next_page<T>(&self, page: &Page<T>) -> ClientResult<Option<Page<T>>>
+    where
+        T: DeserializeOwned + Send + Sync,
+    {
+        match &page.next {
+            Some(next) => {
+                let result = self.endpoint_get(next, &Query::new()).await?;
+                convert_result(&result).map(Some)
+            }
+            None => Ok(None),
+        }
+    }
 }
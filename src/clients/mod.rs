@@ -5,17 +5,97 @@ pub mod pagination;
 pub use base::BaseClient;
 pub use oauth::OAuthClient;
 
-use crate::ClientResult;
+use crate::{http::HttpError, model::ApiError, ClientError, ClientResult, Config};
 
-use std::fmt::Write as _;
+use std::{fmt::Write as _, time::Duration};
 
 use serde::Deserialize;
 
+/// The maximum number of items Spotify returns per page in most of its
+/// paginated endpoints.
+pub(crate) const PAGE_LIMIT: u32 = 50;
+
+/// The maximum number of items Spotify returns per page when fetching the
+/// items of a playlist.
+pub(crate) const PLAYLIST_ITEMS_PAGE_LIMIT: u32 = 100;
+
+/// The page size for an automatically paginated endpoint, which is the
+/// configured [`pagination_chunks`](crate::Config::pagination_chunks) clamped
+/// to what the endpoint accepts.
+pub(crate) fn page_size(config: &Config, max: u32) -> u32 {
+    config.pagination_chunks.clamp(1, max)
+}
+
 /// Converts a JSON response from Spotify into its model.
 pub(crate) fn convert_result<'a, T: Deserialize<'a>>(input: &'a str) -> ClientResult<T> {
     serde_json::from_str::<T>(input).map_err(Into::into)
 }
 
+/// Same as [`convert_result`], but for the endpoints that respond with
+/// `204 No Content` and an empty body when there's nothing to return, e.g.
+/// when nothing is playing. In that case, `None` is returned.
+pub(crate) fn convert_optional_result<'a, T: Deserialize<'a>>(
+    input: &'a str,
+) -> ClientResult<Option<T>> {
+    if input.trim().is_empty() {
+        Ok(None)
+    } else {
+        convert_result(input)
+    }
+}
+
+/// Parses the value of a `Retry-After` header, which Spotify sends as a number
+/// of seconds.
+fn parse_retry_after(value: &str) -> Option<Duration> {
+    value.trim().parse().ok().map(Duration::from_secs)
+}
+
+/// Converts an error from the HTTP client into a [`ClientError`]. The body of
+/// unsuccessful responses is read into an [`ApiError`] so that the details
+/// Spotify gives about the error aren't lost.
+#[cfg(feature = "client-reqwest")]
+pub(crate) async fn convert_http_error(err: HttpError) -> ClientError {
+    match err {
+        HttpError::StatusCode(response) => {
+            let status = response.status().as_u16();
+            let retry_after = response
+                .headers()
+                .get("Retry-After")
+                .and_then(|value| value.to_str().ok())
+                .and_then(parse_retry_after);
+            match response.text().await {
+                Ok(body) => ClientError::Api(ApiError {
+                    retry_after,
+                    ..ApiError::from_response(status, &body)
+                }),
+                Err(err) => HttpError::Client(err).into(),
+            }
+        }
+        err => err.into(),
+    }
+}
+
+/// Converts an error from the HTTP client into a [`ClientError`]. The body of
+/// unsuccessful responses is read into an [`ApiError`] so that the details
+/// Spotify gives about the error aren't lost.
+#[cfg(feature = "client-ureq")]
+pub(crate) fn convert_http_error(err: HttpError) -> ClientError {
+    match err {
+        HttpError::StatusCode(response) => {
+            let status = response.status();
+            let retry_after = response.header("Retry-After").and_then(parse_retry_after);
+            match response.into_string() {
+                Ok(body) => ClientError::Api(ApiError {
+                    retry_after,
+                    ..ApiError::from_response(status, &body)
+                }),
+                Err(err) => HttpError::Io(err).into(),
+            }
+        }
+        err => err.into(),
+    }
+}
+
 /// Append device ID to an API path.
 pub(crate) fn append_device_id(path: &str, device_id: Option<&str>) -> String {
     let mut new_path = path.to_string();
@@ -32,9 +112,41 @@ pub(crate) fn append_device_id(path: &str, device_id: Option<&str>) -> String {
 #[cfg(test)]
 mod test {
     use super::*;
-    use crate::{model::Token, scopes, ClientCredsSpotify};
+    use crate::{
+        model::{
+            AlbumId, AlbumType, ArtistId, Country, IdError, Market, Page, PlaylistId,
+            RecommendationsAttribute, Token, TrackId, UserId,
+        },
+        scopes,
+        util::build_map,
+        AuthCodePkceSpotify, AuthCodeSpotify, ClientCredsSpotify, ClientError,
+    };
     use chrono::{prelude::*, Duration};
 
+    #[test]
+    fn test_convert_optional_result() {
+        for empty in ["", " \n", "null"] {
+            let result = convert_optional_result::<Page<u32>>(empty).unwrap();
+            assert_eq!(result, None);
+        }
+
+        let json = r#"{"href": "", "items": [1], "limit": 1, "next": null,
+            "offset": 0, "previous": null, "total": 1}"#;
+        let page = convert_optional_result::<Page<u32>>(json).unwrap().unwrap();
+        assert_eq!(page.items, [1]);
+
+        let result = convert_optional_result::<Page<u32>>("{");
+        assert!(matches!(result, Err(ClientError::ParseJson(_))));
+    }
+
+    #[test]
+    fn test_parse_retry_after() {
+        let secs = std::time::Duration::from_secs;
+        assert_eq!(parse_retry_after("3"), Some(secs(3)));
+        assert_eq!(parse_retry_after(" 120 "), Some(secs(120)));
+        assert_eq!(parse_retry_after("Wed, 21 Oct 2015 07:28:00 GMT"), None);
+    }
+
     #[test]
     fn test_append_device_id_without_question_mark() {
         let path = "me/player/play";
@@ -54,6 +166,33 @@ mod test {
         );
     }
 
+    #[test]
+    fn test_page_size() {
+        let config = |pagination_chunks| Config {
+            pagination_chunks,
+            ..Default::default()
+        };
+        assert_eq!(page_size(&config(20), PAGE_LIMIT), 20);
+        assert_eq!(page_size(&config(100), PAGE_LIMIT), 50);
+        assert_eq!(page_size(&config(100), PLAYLIST_ITEMS_PAGE_LIMIT), 100);
+        assert_eq!(page_size(&config(0), PAGE_LIMIT), 1);
+    }
+
+    #[test]
+    fn test_market_query() {
+        let market = Some(Market::FromToken);
+        let params = build_map([("market", market.map(Into::into))]);
+        assert_eq!(params.get("market"), Some(&"from_token"));
+
+        let market = Some(Market::Country(Country::UnitedStates));
+        let params = build_map([("market", market.map(Into::into))]);
+        assert_eq!(params.get("market"), Some(&"US"));
+
+        let market: Option<Market> = None;
+        let params = build_map([("market", market.map(Into::into))]);
+        assert!(params.is_empty());
+    }
+
     #[test]
     fn test_endpoint_url() {
         let spotify = ClientCredsSpotify::default();
@@ -71,6 +210,162 @@ mod test {
         );
     }
 
+    #[test]
+    fn test_pkce_verifier_stored() {
+        use sha2::{Digest, Sha256};
+
+        let mut spotify = AuthCodePkceSpotify::default();
+        let url = spotify.get_authorize_url(None).unwrap();
+        let verifier = spotify.verifier.clone().unwrap();
+        assert_eq!(verifier.len(), 43);
+
+        // The challenge sent in the URL must be derived from the stored
+        // verifier, which is later sent when requesting the token.
+        let url = url::Url::parse(&url).unwrap();
+        let challenge = url
+            .query_pairs()
+            .find(|(key, _)| key == "code_challenge")
+            .map(|(_, value)| value.into_owned());
+        let expected =
+            base64::encode_config(Sha256::digest(verifier.as_bytes()), base64::URL_SAFE_NO_PAD);
+        assert_eq!(challenge, Some(expected));
+
+        // A new verifier is generated for every authorization URL
+        spotify.get_authorize_url(Some(128)).unwrap();
+        assert_ne!(spotify.verifier, Some(verifier));
+    }
+
+    #[maybe_async::test(feature = "__sync", async(feature = "__async", tokio::test))]
+    async fn test_playlist_upload_cover_image_too_large() {
+        let spotify = AuthCodeSpotify::default();
+        let playlist_id = PlaylistId::from_id("37i9dQZF1DXcBWIGoYBM5M").unwrap();
+        let image = base64::encode(vec![0u8; 256 * 1024 + 1]);
+
+        let result = spotify
+            .playlist_upload_cover_image(playlist_id, &image)
+            .await;
+        assert!(matches!(result, Err(ClientError::InvalidInput(_))));
+    }
+
+    #[maybe_async::test(feature = "__sync", async(feature = "__async", tokio::test))]
+    async fn test_too_many_ids() {
+        let spotify = AuthCodeSpotify::default();
+        let album_id = AlbumId::from_id("4aawyAB9vmqN3uQ7FjRGTy").unwrap();
+
+        let result = spotify
+            .current_user_saved_albums_add(vec![album_id; 21])
+            .await;
+        assert!(matches!(
+            result,
+            Err(ClientError::TooManyIds { max: 20, got: 21 })
+        ));
+    }
+
+    #[maybe_async::test(feature = "__sync", async(feature = "__async", tokio::test))]
+    async fn test_artist_albums_duplicated_groups() {
+        let spotify = ClientCredsSpotify::default();
+        let artist_id = ArtistId::from_id("2WX2uTcsvV5OnS0inACecP").unwrap();
+
+        let result = spotify
+            .artist_albums_manual(
+                artist_id,
+                &[AlbumType::Album, AlbumType::Single, AlbumType::Album],
+                None,
+                None,
+                None,
+            )
+            .await;
+        assert!(matches!(result, Err(ClientError::InvalidInput(_))));
+    }
+
+    #[maybe_async::test(feature = "__sync", async(feature = "__async", tokio::test))]
+    async fn test_collaborative_public_playlist() {
+        let spotify = AuthCodeSpotify::default();
+        let user_id = UserId::from_id("possan").unwrap();
+
+        for public in [None, Some(true)] {
+            let result = spotify
+                .user_playlist_create(user_id.as_ref(), "name", public, Some(true), None)
+                .await;
+            assert!(matches!(result, Err(ClientError::InvalidInput(_))));
+        }
+    }
+
+    #[maybe_async::test(feature = "__sync", async(feature = "__async", tokio::test))]
+    async fn test_next_page_of_last_page() {
+        let spotify = ClientCredsSpotify::default();
+        let page = Page::<u32> {
+            next: None,
+            ..Default::default()
+        };
+
+        let next = spotify.next_page(&page).await.unwrap();
+        assert_eq!(next, None);
+    }
+
+    #[maybe_async::test(feature = "__sync", async(feature = "__async", tokio::test))]
+    async fn test_resolve_invalid_uri() {
+        let spotify = ClientCredsSpotify::default();
+
+        for (uri, expected) in [
+            (
+                "spotify:chapter:0D5wENdkdwbqlrHoaJ9g29",
+                IdError::InvalidType,
+            ),
+            (
+                "spotify:unknown:0D5wENdkdwbqlrHoaJ9g29",
+                IdError::InvalidType,
+            ),
+            ("not a uri", IdError::InvalidPrefix),
+            ("spotify:track", IdError::InvalidFormat),
+            // The error of a malformed URL is the one for URLs
+            ("https://open.spotify.com/track", IdError::InvalidFormat),
+            (
+                "https://example.com/track/0D5wENdkdwbqlrHoaJ9g29",
+                IdError::InvalidPrefix,
+            ),
+        ] {
+            let result = spotify.resolve_uri(uri, None).await;
+            assert!(
+                matches!(&result, Err(ClientError::InvalidId(err)) if *err == expected),
+                "{}: {:?}",
+                uri,
+                result
+            );
+        }
+    }
+
+    #[maybe_async::test(feature = "__sync", async(feature = "__async", tokio::test))]
+    async fn test_recommendations_seeds() {
+        let spotify = ClientCredsSpotify::default();
+        let attributes = [RecommendationsAttribute::MinEnergy(0.4)];
+
+        let result = spotify
+            .recommendations(
+                attributes,
+                None::<Vec<ArtistId>>,
+                None::<Vec<&str>>,
+                None::<Vec<TrackId>>,
+                None,
+                None,
+            )
+            .await;
+        assert!(matches!(result, Err(ClientError::InvalidInput(_))));
+
+        let genres = ["rock", "pop", "jazz", "blues", "folk", "punk"];
+        let result = spotify
+            .recommendations(
+                attributes,
+                None::<Vec<ArtistId>>,
+                Some(genres),
+                None::<Vec<TrackId>>,
+                None,
+                None,
+            )
+            .await;
+        assert!(matches!(result, Err(ClientError::InvalidInput(_))));
+    }
+
     #[maybe_async::test(feature = "__sync", async(feature = "__async", tokio::test))]
     async fn test_auth_headers() {
         let tok = Token {
@@ -79,6 +374,7 @@ mod test {
             expires_at: Some(Utc::now()),
             scopes: scopes!("playlist-read-private"),
             refresh_token: Some("...".to_string()),
+            ..Default::default()
         };
 
         let spotify = ClientCredsSpotify::from_token(tok);
@@ -1,14 +1,14 @@
 use crate::{
     clients::{
-        append_device_id, convert_result,
-        pagination::{paginate, Paginator},
-        BaseClient,
+        append_device_id, convert_optional_result, convert_result, page_size,
+        pagination::{paginate, paginate_cursor, poll, Paginator},
+        BaseClient, PAGE_LIMIT,
     },
     http::Query,
-    join_ids,
+    join_ids_limited,
     model::*,
     util::{build_map, JsonBuilder},
-    ClientResult, OAuth, Token,
+    ClientError, ClientResult, OAuth, Token,
 };
 
 use std::{collections::HashMap, time};
@@ -18,6 +18,10 @@ use rspotify_model::idtypes::{PlayContextId, PlayableId};
 use serde_json::{json, Map};
 use url::Url;
 
+/// The maximum number of items Spotify accepts in a single request to add or
+/// remove items from a playlist.
+const PLAYLIST_ITEMS_LIMIT: usize = 100;
+
 /// This trait implements the methods available strictly to clients with user
 /// authorization, including some parts of the authentication flow that are
 /// shared, and the endpoints.
@@ -27,6 +31,29 @@ use url::Url;
 /// [`user_playlist`](crate::clients::BaseClient::user_playlist). This trait
 /// only separates endpoints that *always* need authorization from the base
 /// ones.
+///
+/// The player endpoints that control the playback require the user to have
+/// Spotify Premium. Otherwise, they fail with a [`ClientError::Api`] whose
+/// `reason` is [`PlayerErrorReason::PremiumRequired`]. Similarly, the reason
+/// is [`PlayerErrorReason::NoActiveDevice`] if there's no device to control:
+///
+/// ```
+/// use rspotify::{model::{ApiError, PlayerErrorReason}, ClientError};
+///
+/// fn describe(err: &ClientError) -> &str {
+///     match err {
+///         ClientError::Api(ApiError {
+///             reason: Some(PlayerErrorReason::PremiumRequired),
+///             ..
+///         }) => "Upgrade to Spotify Premium to control the playback",
+///         ClientError::Api(ApiError {
+///             reason: Some(PlayerErrorReason::NoActiveDevice),
+///             ..
+///         }) => "Start playing on any of your devices first",
+///         _ => "Something went wrong",
+///     }
+/// }
+/// ```
 #[maybe_async]
 pub trait OAuthClient: BaseClient {
     fn get_oauth(&self) -> &OAuth;
@@ -35,11 +62,12 @@ pub trait OAuthClient: BaseClient {
     /// authentication. The access token will be saved internally.
     async fn request_token(&self, code: &str) -> ClientResult<()>;
 
-    /// Tries to read the cache file's token.
+    /// Tries to read the cached token.
     ///
-    /// This will return an error if the token couldn't be read (e.g. it's not
-    /// available or the JSON is malformed). It may return `Ok(None)` if:
+    /// This will return an error if the token couldn't be read (e.g. the JSON
+    /// is malformed). It may return `Ok(None)` if:
     ///
+    /// * There's no token in the cache
     /// * The read token is expired and `allow_expired` is false
     /// * Its scopes don't match with the current client (you will need to
     ///   re-authenticate to gain access to more scopes)
@@ -63,12 +91,17 @@ pub trait OAuthClient: BaseClient {
         }
 
         log::info!("Reading auth token cache");
-        let token = Token::from_cache(&self.get_config().cache_path)?;
-        if !self.get_oauth().scopes.is_subset(&token.scopes)
-            || (!allow_expired && token.is_expired())
-        {
+        let token = match self.get_config().token_cache().load().await? {
+            Some(token) => token,
+            None => return Ok(None),
+        };
+        if !token.has_scopes(&self.get_oauth().scopes) {
             // Invalid token, since it doesn't have at least the currently
-            // required scopes or it's expired.
+            // required scopes, so the user has to authenticate again.
+            log::info!("Cached token is missing some of the required scopes");
+            Ok(None)
+        } else if !allow_expired && token.is_expired_with_skew(self.get_config().token_expiry_skew)
+        {
             Ok(None)
         } else {
             Ok(Some(token))
@@ -78,25 +111,35 @@ pub trait OAuthClient: BaseClient {
     /// Parse the response code in the given response url. If the URL cannot be
     /// parsed or the `code` parameter is not present, this will return `None`.
     ///
-    // As the [RFC
-    // indicates](https://datatracker.ietf.org/doc/html/rfc6749#section-4.1),
-    // the state should be the same between the request and the callback. This
-    // will also return `None` if this is not true.
+    /// See [`Self::try_parse_response_code`] for a version that reports why
+    /// the code couldn't be obtained.
     fn parse_response_code(&self, url: &str) -> Option<String> {
-        let url = Url::parse(url).ok()?;
+        self.try_parse_response_code(url).ok()
+    }
+
+    /// Parse the response code in the given response url.
+    ///
+    /// As the [RFC
+    /// indicates](https://datatracker.ietf.org/doc/html/rfc6749#section-4.1),
+    /// the state should be the same between the request and the callback, so
+    /// this fails with [`ClientError::InvalidState`] if it's not.
+    fn try_parse_response_code(&self, url: &str) -> ClientResult<String> {
+        let url = Url::parse(url)?;
         let params = url.query_pairs().collect::<HashMap<_, _>>();
 
-        let code = params.get("code")?;
+        let code = params.get("code").ok_or_else(|| {
+            ClientError::InvalidInput("the callback has no `code` parameter".to_string())
+        })?;
 
         // Making sure the state is the same
         let expected_state = &self.get_oauth().state;
         let state = params.get("state").map(AsRef::as_ref);
         if state != Some(expected_state) {
             log::error!("Request state doesn't match the callback state");
-            return None;
+            return Err(ClientError::InvalidState);
         }
 
-        Some(code.to_string())
+        Ok(code.to_string())
     }
 
     /// Tries to open the authorization URL in the user's browser, and returns
@@ -105,27 +148,107 @@ pub trait OAuthClient: BaseClient {
     /// Note: this method requires the `cli` feature.
     #[cfg(feature = "cli")]
     fn get_code_from_user(&self, url: &str) -> ClientResult<String> {
-        use crate::ClientError;
-
-        log::info!("Opening brower with auth URL");
-        match webbrowser::open(url) {
-            Ok(_) => println!("Opened {} in your browser.", url),
-            Err(why) => eprintln!(
-                "Error when trying to open an URL in your browser: {:?}. \
-                 Please navigate here manually: {}",
-                why, url
-            ),
-        }
+        open_in_browser(url);
 
         log::info!("Prompting user for code");
         println!("Please enter the URL you were redirected to: ");
         let mut input = String::new();
         std::io::stdin().read_line(&mut input)?;
-        let code = self
-            .parse_response_code(&input)
-            .ok_or_else(|| ClientError::Cli("unable to parse the response code".to_string()))?;
+        self.try_parse_response_code(input.trim())
+    }
+
+    /// Tries to open the authorization URL in the user's browser, and obtains
+    /// the code by listening on the redirect URI for the callback, so that the
+    /// user doesn't have to copy it manually.
+    ///
+    /// The redirect URI must be a local HTTP one, like
+    /// `http://localhost:8888/callback`. Callbacks whose state doesn't match
+    /// the client's are rejected, as well as the ones where the user denied
+    /// access.
+    ///
+    /// Note: this method requires the `cli` feature.
+    #[cfg(feature = "cli")]
+    fn get_code_from_local_server(&self, url: &str) -> ClientResult<String> {
+        use std::{
+            io::{BufRead, BufReader, Write},
+            net::TcpListener,
+            time::Duration,
+        };
+
+        let redirect_uri = Url::parse(&self.get_oauth().redirect_uri)
+            .map_err(|err| ClientError::Cli(format!("invalid redirect URI: {err}")))?;
+        let addr = local_server_addr(&redirect_uri).ok_or_else(|| {
+            ClientError::Cli(format!(
+                "the redirect URI {redirect_uri} must be a local HTTP one to listen on it"
+            ))
+        })?;
+
+        log::info!("Listening for the callback on {}:{}", addr.0, addr.1);
+        let listener = TcpListener::bind(addr)?;
+        open_in_browser(url);
+
+        for stream in listener.incoming() {
+            // Connections that fail or don't send anything in time, like the
+            // ones browsers open speculatively, are skipped so that they
+            // don't block the actual callback.
+            let stream = stream.and_then(|stream| {
+                stream.set_read_timeout(Some(Duration::from_secs(5)))?;
+                Ok(stream)
+            });
+            let mut stream = match stream {
+                Ok(stream) => stream,
+                Err(err) => {
+                    log::warn!("Skipping failed connection: {err}");
+                    continue;
+                }
+            };
+            let mut request_line = String::new();
+            if let Err(err) = BufReader::new(&stream).read_line(&mut request_line) {
+                log::warn!("Skipping failed connection: {err}");
+                continue;
+            }
+
+            // Other requests, like the browser asking for a favicon, are
+            // ignored until the actual callback arrives.
+            let callback = match callback_url(&redirect_uri, &request_line) {
+                Some(callback) => callback,
+                None => {
+                    let _ =
+                        stream.write_all(b"HTTP/1.1 404 Not Found\r\nConnection: close\r\n\r\n");
+                    continue;
+                }
+            };
+
+            let error = callback
+                .query_pairs()
+                .find(|(key, _)| key == "error")
+                .map(|(_, val)| val.into_owned());
+            let result = match error {
+                Some(error) => Err(ClientError::Cli(format!("authorization failed: {error}"))),
+                None => self.try_parse_response_code(callback.as_str()),
+            };
+
+            let message = match &result {
+                Ok(_) => "Authorization succeeded, you may close this window now.".to_owned(),
+                Err(err) => format!("Authorization failed: {err}"),
+            };
+            // The result is already known at this point, so failing to show
+            // it in the browser doesn't make the authorization fail
+            let written = write!(
+                stream,
+                "HTTP/1.1 200 OK\r\nContent-Type: text/plain; charset=utf-8\r\n\
+                 Content-Length: {}\r\nConnection: close\r\n\r\n{}",
+                message.len(),
+                message
+            );
+            if let Err(err) = written {
+                log::warn!("Couldn't respond to the callback: {err}");
+            }
 
-        Ok(code)
+            return result;
+        }
+
+        unreachable!("TcpListener::incoming never returns None")
     }
 
     /// Opens up the authorization URL in the user's browser so that it can
@@ -145,38 +268,18 @@ pub trait OAuthClient: BaseClient {
     #[cfg(feature = "cli")]
     #[maybe_async]
     async fn prompt_for_token(&self, url: &str) -> ClientResult<()> {
-        match self.read_token_cache(true).await {
-            Ok(Some(new_token)) => {
-                let expired = new_token.is_expired();
-
-                // Load token into client regardless of whether it's expired o
-                // not, since it will be refreshed later anyway.
-                *self.get_token().lock().await.unwrap() = Some(new_token);
-
-                if expired {
-                    // Ensure that we actually got a token from the refetch
-                    match self.refetch_token().await? {
-                        Some(refreshed_token) => {
-                            log::info!("Successfully refreshed expired token from token cache");
-                            *self.get_token().lock().await.unwrap() = Some(refreshed_token)
-                        }
-                        // If not, prompt the user for it
-                        None => {
-                            log::info!("Unable to refresh expired token from token cache");
-                            let code = self.get_code_from_user(url)?;
-                            self.request_token(&code).await?;
-                        }
-                    }
-                }
-            }
-            // Otherwise following the usual procedure to get the token.
-            _ => {
-                let code = self.get_code_from_user(url)?;
-                self.request_token(&code).await?;
-            }
-        }
+        prompt_for_token_with(self, url, Self::get_code_from_user).await
+    }
 
-        self.write_token_cache().await
+    /// The same as [`Self::prompt_for_token`], but the code is obtained with
+    /// [`Self::get_code_from_local_server`] instead of reading the redirect
+    /// URI from the standard input.
+    ///
+    /// Note: this method requires the `cli` feature.
+    #[cfg(feature = "cli")]
+    #[maybe_async]
+    async fn prompt_for_token_local_server(&self, url: &str) -> ClientResult<()> {
+        prompt_for_token_with(self, url, Self::get_code_from_local_server).await
     }
 
     /// Get current user playlists without required getting his profile.
@@ -192,7 +295,7 @@ pub trait OAuthClient: BaseClient {
     fn current_user_playlists(&self) -> Paginator<'_, ClientResult<SimplifiedPlaylist>> {
         paginate(
             move |limit, offset| self.current_user_playlists_manual(Some(limit), Some(offset)),
-            self.get_config().pagination_chunks,
+            page_size(self.get_config(), PAGE_LIMIT),
         )
     }
 
@@ -218,7 +321,8 @@ pub trait OAuthClient: BaseClient {
     /// - public - is the created playlist public
     /// - description - the description of the playlist
     /// - collaborative - if the playlist will be collaborative. Note:
-    /// to create a collaborative playlist you must also set public to false
+    ///   to create a collaborative playlist you must also set public to
+    ///   false, or this fails with [`ClientError::InvalidInput`].
     ///
     /// [Reference](https://developer.spotify.com/documentation/web-api/reference/#/operations/create-playlist)
     async fn user_playlist_create(
@@ -229,11 +333,12 @@ pub trait OAuthClient: BaseClient {
         collaborative: Option<bool>,
         description: Option<&str>,
     ) -> ClientResult<FullPlaylist> {
-        debug_assert!(
-            !(collaborative.unwrap_or(false) && public.unwrap_or(false)),
-            "To create a collaborative playlist you must also set public to \
-            false. See the reference for more information."
-        );
+        // Playlists are public by default
+        if collaborative == Some(true) && public != Some(false) {
+            return Err(ClientError::InvalidInput(
+                "collaborative playlists must have `public` set to false".to_owned(),
+            ));
+        }
 
         let params = JsonBuilder::new()
             .required("name", name)
@@ -247,7 +352,8 @@ pub trait OAuthClient: BaseClient {
         convert_result(&result)
     }
 
-    /// Changes a playlist's name and/or public/private state.
+    /// Changes a playlist's name and/or public/private state. Only the given
+    /// details are modified.
     ///
     /// Parameters:
     /// - playlist_id - the id of the playlist
@@ -264,7 +370,7 @@ pub trait OAuthClient: BaseClient {
         public: Option<bool>,
         description: Option<&str>,
         collaborative: Option<bool>,
-    ) -> ClientResult<String> {
+    ) -> ClientResult<()> {
         let params = JsonBuilder::new()
             .optional("name", name)
             .optional("public", public)
@@ -273,7 +379,40 @@ pub trait OAuthClient: BaseClient {
             .build();
 
         let url = format!("playlists/{}", playlist_id.id());
-        self.endpoint_put(&url, &params).await
+        self.endpoint_put(&url, &params).await?;
+
+        Ok(())
+    }
+
+    /// Replace the image used to represent a specific playlist.
+    ///
+    /// Parameters:
+    /// - playlist_id - the id of the playlist
+    /// - image_base64 - the Base64 encoded JPEG image data, up to 256 KB once
+    ///   decoded
+    ///
+    /// [Reference](https://developer.spotify.com/documentation/web-api/reference/#/operations/upload-custom-playlist-cover)
+    async fn playlist_upload_cover_image(
+        &self,
+        playlist_id: PlaylistId<'_>,
+        image_base64: &str,
+    ) -> ClientResult<()> {
+        const MAX_IMAGE_SIZE: usize = 256 * 1024;
+
+        let image = base64::decode(image_base64)
+            .map_err(|err| ClientError::InvalidInput(format!("invalid base64 image: {err}")))?;
+        if image.len() > MAX_IMAGE_SIZE {
+            return Err(ClientError::InvalidInput(format!(
+                "the image is {} bytes long, but the maximum is {MAX_IMAGE_SIZE}",
+                image.len()
+            )));
+        }
+
+        let url = format!("playlists/{}/images", playlist_id.id());
+        self.endpoint_put_raw(&url, "image/jpeg", image_base64)
+            .await?;
+
+        Ok(())
     }
 
     /// Unfollows (deletes) a playlist for a user.
@@ -296,6 +435,10 @@ pub trait OAuthClient: BaseClient {
     /// - track_ids - a list of track URIs, URLs or IDs
     /// - position - the position to add the tracks
     ///
+    /// Spotify only accepts up to 100 items per request, so longer lists are
+    /// added with multiple consecutive requests. The snapshot ID of the last
+    /// one is returned.
+    ///
     /// [Reference](https://developer.spotify.com/documentation/web-api/reference/#/operations/add-tracks-to-playlist)
     async fn playlist_add_items<'a>(
         &self,
@@ -304,14 +447,24 @@ pub trait OAuthClient: BaseClient {
         position: Option<i32>,
     ) -> ClientResult<PlaylistResult> {
         let uris = items.into_iter().map(|id| id.uri()).collect::<Vec<_>>();
-        let params = JsonBuilder::new()
-            .required("uris", uris)
-            .optional("position", position)
-            .build();
-
         let url = format!("playlists/{}/tracks", playlist_id.id());
-        let result = self.endpoint_post(&url, &params).await?;
-        convert_result(&result)
+
+        // At least one request is made, even if there are no items.
+        let mut start = 0;
+        loop {
+            let end = uris.len().min(start + PLAYLIST_ITEMS_LIMIT);
+            let params = JsonBuilder::new()
+                .required("uris", &uris[start..end])
+                .optional("position", position.map(|pos| pos + start as i32))
+                .build();
+
+            let result = self.endpoint_post(&url, &params).await?;
+            let result = convert_result::<PlaylistResult>(&result)?;
+            if end == uris.len() {
+                return Ok(result);
+            }
+            start = end;
+        }
     }
 
     /// Replace all items in a playlist
@@ -326,14 +479,13 @@ pub trait OAuthClient: BaseClient {
         &self,
         playlist_id: PlaylistId<'_>,
         items: impl IntoIterator<Item = PlayableId<'a>> + Send + 'a,
-    ) -> ClientResult<()> {
+    ) -> ClientResult<PlaylistResult> {
         let uris = items.into_iter().map(|id| id.uri()).collect::<Vec<_>>();
         let params = JsonBuilder::new().required("uris", uris).build();
 
         let url = format!("playlists/{}/tracks", playlist_id.id());
-        self.endpoint_put(&url, &params).await?;
-
-        Ok(())
+        let result = self.endpoint_put(&url, &params).await?;
+        convert_result(&result)
     }
 
     /// Reorder items in a playlist.
@@ -375,6 +527,11 @@ pub trait OAuthClient: BaseClient {
     /// - track_ids - the list of track ids to add to the playlist
     /// - snapshot_id - optional id of the playlist snapshot
     ///
+    /// Spotify only accepts up to 100 items per request, so longer lists are
+    /// removed with multiple consecutive requests, each of them using the
+    /// snapshot ID returned by the previous one. The snapshot ID of the last
+    /// one is returned.
+    ///
     /// [Reference](https://developer.spotify.com/documentation/web-api/reference/#/operations/remove-tracks-playlist)
     async fn playlist_remove_all_occurrences_of_items<'a>(
         &self,
@@ -390,15 +547,26 @@ pub trait OAuthClient: BaseClient {
                 map
             })
             .collect::<Vec<_>>();
-
-        let params = JsonBuilder::new()
-            .required("tracks", tracks)
-            .optional("snapshot_id", snapshot_id)
-            .build();
-
         let url = format!("playlists/{}/tracks", playlist_id.id());
-        let result = self.endpoint_delete(&url, &params).await?;
-        convert_result(&result)
+
+        // At least one request is made, even if there are no items.
+        let mut snapshot_id = snapshot_id.map(ToOwned::to_owned);
+        let mut start = 0;
+        loop {
+            let end = tracks.len().min(start + PLAYLIST_ITEMS_LIMIT);
+            let params = JsonBuilder::new()
+                .required("tracks", &tracks[start..end])
+                .optional("snapshot_id", snapshot_id.as_deref())
+                .build();
+
+            let result = self.endpoint_delete(&url, &params).await?;
+            let result = convert_result::<PlaylistResult>(&result)?;
+            if end == tracks.len() {
+                return Ok(result);
+            }
+            snapshot_id = Some(result.snapshot_id);
+            start = end;
+        }
     }
 
     /// Removes specfic occurrences of the given items from the given playlist.
@@ -476,6 +644,20 @@ pub trait OAuthClient: BaseClient {
         Ok(())
     }
 
+    /// Check if the current authenticated user follows a playlist. This is a
+    /// shortcut for [`BaseClient::playlist_check_follow`] with the ID of the
+    /// current user, which is obtained with [`Self::me`] first.
+    ///
+    /// Parameters:
+    /// - playlist_id - the id of the playlist
+    ///
+    /// [Reference](https://developer.spotify.com/documentation/web-api/reference/#/operations/check-if-user-follows-playlist)
+    async fn me_follows_playlist(&self, playlist_id: PlaylistId<'_>) -> ClientResult<bool> {
+        let user_id = self.me().await?.id;
+        let follows = self.playlist_check_follow(playlist_id, [user_id]).await?;
+        Ok(follows.first().copied().unwrap_or(false))
+    }
+
     /// Get detailed profile information about the current user.
     /// An alias for the 'current_user' method.
     ///
@@ -500,11 +682,7 @@ pub trait OAuthClient: BaseClient {
         let result = self
             .endpoint_get("me/player/currently-playing", &Query::new())
             .await?;
-        if result.is_empty() {
-            Ok(None)
-        } else {
-            convert_result(&result)
-        }
+        convert_optional_result(&result)
     }
 
     /// Gets a list of the albums saved in the current authorized user's
@@ -527,7 +705,7 @@ pub trait OAuthClient: BaseClient {
             move |limit, offset| {
                 self.current_user_saved_albums_manual(market, Some(limit), Some(offset))
             },
-            self.get_config().pagination_chunks,
+            page_size(self.get_config(), PAGE_LIMIT),
         )
     }
 
@@ -570,7 +748,7 @@ pub trait OAuthClient: BaseClient {
             move |limit, offset| {
                 self.current_user_saved_tracks_manual(market, Some(limit), Some(offset))
             },
-            self.get_config().pagination_chunks,
+            page_size(self.get_config(), PAGE_LIMIT),
         )
     }
 
@@ -595,12 +773,30 @@ pub trait OAuthClient: BaseClient {
 
     /// Gets a list of the artists followed by the current authorized user.
     ///
+    /// See [`Self::current_user_followed_artists_manual`] for a manually
+    /// paginated version of this.
+    ///
+    /// [Reference](https://developer.spotify.com/documentation/web-api/reference/#/operations/get-followed)
+    fn current_user_followed_artists(&self) -> Paginator<'_, ClientResult<FullArtist>> {
+        paginate_cursor(
+            move |limit, cursors| async move {
+                let after = cursors.and_then(|cursors| cursors.after);
+                self.current_user_followed_artists_manual(after.as_deref(), Some(limit))
+                    .await
+            },
+            page_size(self.get_config(), PAGE_LIMIT),
+        )
+    }
+
+    /// The manually paginated version of
+    /// [`Self::current_user_followed_artists`].
+    ///
     /// Parameters:
     /// - after - the last artist ID retrieved from the previous request
     /// - limit - the number of tracks to return
     ///
     /// [Reference](https://developer.spotify.com/documentation/web-api/reference/#/operations/get-followed)
-    async fn current_user_followed_artists(
+    async fn current_user_followed_artists_manual(
         &self,
         after: Option<&str>,
         limit: Option<u32>,
@@ -626,7 +822,7 @@ pub trait OAuthClient: BaseClient {
         &self,
         track_ids: impl IntoIterator<Item = TrackId<'a>> + Send + 'a,
     ) -> ClientResult<()> {
-        let url = format!("me/tracks/?ids={}", join_ids(track_ids));
+        let url = format!("me/tracks/?ids={}", join_ids_limited(track_ids, 50)?);
         self.endpoint_delete(&url, &json!({})).await?;
 
         Ok(())
@@ -643,11 +839,29 @@ pub trait OAuthClient: BaseClient {
         &self,
         track_ids: impl IntoIterator<Item = TrackId<'a>> + Send + 'a,
     ) -> ClientResult<Vec<bool>> {
-        let url = format!("me/tracks/contains/?ids={}", join_ids(track_ids));
+        let url = format!(
+            "me/tracks/contains/?ids={}",
+            join_ids_limited(track_ids, 50)?
+        );
         let result = self.endpoint_get(&url, &Query::new()).await?;
         convert_result(&result)
     }
 
+    /// Like [`Self::current_user_saved_tracks_contains`], but returns whether
+    /// each of the given tracks is saved keyed by its ID, instead of in the
+    /// same order. Duplicated IDs collapse into a single entry, so the map
+    /// may have fewer entries than the given IDs.
+    async fn current_user_saved_tracks_contains_map<'a>(
+        &self,
+        track_ids: impl IntoIterator<Item = TrackId<'a>> + Send + 'a,
+    ) -> ClientResult<HashMap<TrackId<'a>, bool>> {
+        let track_ids = track_ids.into_iter().collect::<Vec<_>>();
+        let saved = self
+            .current_user_saved_tracks_contains(track_ids.clone())
+            .await?;
+        Ok(track_ids.into_iter().zip(saved).collect())
+    }
+
     /// Save one or more tracks to the current user's "Your Music" library.
     ///
     /// Parameters:
@@ -658,7 +872,7 @@ pub trait OAuthClient: BaseClient {
         &self,
         track_ids: impl IntoIterator<Item = TrackId<'a>> + Send + 'a,
     ) -> ClientResult<()> {
-        let url = format!("me/tracks/?ids={}", join_ids(track_ids));
+        let url = format!("me/tracks/?ids={}", join_ids_limited(track_ids, 50)?);
         self.endpoint_put(&url, &json!({})).await?;
 
         Ok(())
@@ -669,7 +883,9 @@ pub trait OAuthClient: BaseClient {
     /// Parameters:
     /// - limit - the number of entities to return
     /// - offset - the index of the first entity to return
-    /// - time_range - Over what time frame are the affinities computed
+    /// - time_range - Over what time frame are the affinities computed.
+    ///   Spotify uses `medium_term` (approximately the last 6 months) when
+    ///   it's `None`
     ///
     /// See [`Self::current_user_top_artists_manual`] for a manually paginated
     /// version of this.
@@ -683,7 +899,7 @@ pub trait OAuthClient: BaseClient {
             move |limit, offset| {
                 self.current_user_top_artists_manual(time_range, Some(limit), Some(offset))
             },
-            self.get_config().pagination_chunks,
+            page_size(self.get_config(), PAGE_LIMIT),
         )
     }
 
@@ -711,7 +927,9 @@ pub trait OAuthClient: BaseClient {
     /// Parameters:
     /// - limit - the number of entities to return
     /// - offset - the index of the first entity to return
-    /// - time_range - Over what time frame are the affinities computed
+    /// - time_range - Over what time frame are the affinities computed.
+    ///   Spotify uses `medium_term` (approximately the last 6 months) when
+    ///   it's `None`
     ///
     /// See [`Self::current_user_top_tracks_manual`] for a manually paginated
     /// version of this.
@@ -725,7 +943,7 @@ pub trait OAuthClient: BaseClient {
             move |limit, offset| {
                 self.current_user_top_tracks_manual(time_range, Some(limit), Some(offset))
             },
-            self.get_config().pagination_chunks,
+            page_size(self.get_config(), PAGE_LIMIT),
         )
     }
 
@@ -789,7 +1007,7 @@ pub trait OAuthClient: BaseClient {
         &self,
         album_ids: impl IntoIterator<Item = AlbumId<'a>> + Send + 'a,
     ) -> ClientResult<()> {
-        let url = format!("me/albums/?ids={}", join_ids(album_ids));
+        let url = format!("me/albums/?ids={}", join_ids_limited(album_ids, 20)?);
         self.endpoint_put(&url, &json!({})).await?;
 
         Ok(())
@@ -805,7 +1023,7 @@ pub trait OAuthClient: BaseClient {
         &self,
         album_ids: impl IntoIterator<Item = AlbumId<'a>> + Send + 'a,
     ) -> ClientResult<()> {
-        let url = format!("me/albums/?ids={}", join_ids(album_ids));
+        let url = format!("me/albums/?ids={}", join_ids_limited(album_ids, 20)?);
         self.endpoint_delete(&url, &json!({})).await?;
 
         Ok(())
@@ -822,11 +1040,29 @@ pub trait OAuthClient: BaseClient {
         &self,
         album_ids: impl IntoIterator<Item = AlbumId<'a>> + Send + 'a,
     ) -> ClientResult<Vec<bool>> {
-        let url = format!("me/albums/contains/?ids={}", join_ids(album_ids));
+        let url = format!(
+            "me/albums/contains/?ids={}",
+            join_ids_limited(album_ids, 20)?
+        );
         let result = self.endpoint_get(&url, &Query::new()).await?;
         convert_result(&result)
     }
 
+    /// Like [`Self::current_user_saved_albums_contains`], but returns whether
+    /// each of the given albums is saved keyed by its ID, instead of in the
+    /// same order. Duplicated IDs collapse into a single entry, so the map
+    /// may have fewer entries than the given IDs.
+    async fn current_user_saved_albums_contains_map<'a>(
+        &self,
+        album_ids: impl IntoIterator<Item = AlbumId<'a>> + Send + 'a,
+    ) -> ClientResult<HashMap<AlbumId<'a>, bool>> {
+        let album_ids = album_ids.into_iter().collect::<Vec<_>>();
+        let saved = self
+            .current_user_saved_albums_contains(album_ids.clone())
+            .await?;
+        Ok(album_ids.into_iter().zip(saved).collect())
+    }
+
     /// Follow one or more artists.
     ///
     /// Parameters:
@@ -837,7 +1073,10 @@ pub trait OAuthClient: BaseClient {
         &self,
         artist_ids: impl IntoIterator<Item = ArtistId<'a>> + Send + 'a,
     ) -> ClientResult<()> {
-        let url = format!("me/following?type=artist&ids={}", join_ids(artist_ids));
+        let url = format!(
+            "me/following?type=artist&ids={}",
+            join_ids_limited(artist_ids, 50)?
+        );
         self.endpoint_put(&url, &json!({})).await?;
 
         Ok(())
@@ -853,7 +1092,10 @@ pub trait OAuthClient: BaseClient {
         &self,
         artist_ids: impl IntoIterator<Item = ArtistId<'a>> + Send + 'a,
     ) -> ClientResult<()> {
-        let url = format!("me/following?type=artist&ids={}", join_ids(artist_ids));
+        let url = format!(
+            "me/following?type=artist&ids={}",
+            join_ids_limited(artist_ids, 50)?
+        );
         self.endpoint_delete(&url, &json!({})).await?;
 
         Ok(())
@@ -872,7 +1114,7 @@ pub trait OAuthClient: BaseClient {
     ) -> ClientResult<Vec<bool>> {
         let url = format!(
             "me/following/contains?type=artist&ids={}",
-            join_ids(artist_ids)
+            join_ids_limited(artist_ids, 50)?
         );
         let result = self.endpoint_get(&url, &Query::new()).await?;
         convert_result(&result)
@@ -881,14 +1123,17 @@ pub trait OAuthClient: BaseClient {
     /// Follow one or more users.
     ///
     /// Parameters:
-    /// - user_ids - a list of artist IDs
+    /// - user_ids - a list of user IDs
     ///
     /// [Reference](https://developer.spotify.com/documentation/web-api/reference/#/operations/follow-artists-users)
     async fn user_follow_users<'a>(
         &self,
         user_ids: impl IntoIterator<Item = UserId<'a>> + Send + 'a,
     ) -> ClientResult<()> {
-        let url = format!("me/following?type=user&ids={}", join_ids(user_ids));
+        let url = format!(
+            "me/following?type=user&ids={}",
+            join_ids_limited(user_ids, 50)?
+        );
         self.endpoint_put(&url, &json!({})).await?;
 
         Ok(())
@@ -897,19 +1142,41 @@ pub trait OAuthClient: BaseClient {
     /// Unfollow one or more users.
     ///
     /// Parameters:
-    /// - user_ids - a list of artist IDs
+    /// - user_ids - a list of user IDs
     ///
     /// [Reference](https://developer.spotify.com/documentation/web-api/reference/#/operations/unfollow-artists-users)
     async fn user_unfollow_users<'a>(
         &self,
         user_ids: impl IntoIterator<Item = UserId<'a>> + Send + 'a,
     ) -> ClientResult<()> {
-        let url = format!("me/following?type=user&ids={}", join_ids(user_ids));
+        let url = format!(
+            "me/following?type=user&ids={}",
+            join_ids_limited(user_ids, 50)?
+        );
         self.endpoint_delete(&url, &json!({})).await?;
 
         Ok(())
     }
 
+    /// Check to see if the current user is following one or more other
+    /// Spotify users.
+    ///
+    /// Parameters:
+    /// - user_ids - the ids of the users to check, up to 50
+    ///
+    /// [Reference](https://developer.spotify.com/documentation/web-api/reference/#/operations/check-current-user-follows)
+    async fn user_is_following_users<'a>(
+        &self,
+        user_ids: impl IntoIterator<Item = UserId<'a>> + Send + 'a,
+    ) -> ClientResult<Vec<bool>> {
+        let url = format!(
+            "me/following/contains?type=user&ids={}",
+            join_ids_limited(user_ids, 50)?
+        );
+        let result = self.endpoint_get(&url, &Query::new()).await?;
+        convert_result(&result)
+    }
+
     /// Get a User’s Available Devices
     ///
     /// [Reference](https://developer.spotify.com/documentation/web-api/reference/#/operations/get-a-users-available-devices)
@@ -931,7 +1198,7 @@ pub trait OAuthClient: BaseClient {
     /// [Reference](https://developer.spotify.com/documentation/web-api/reference/#/operations/get-information-about-the-users-current-playback)
     async fn current_playback<'a>(
         &self,
-        country: Option<Market>,
+        market: Option<Market>,
         additional_types: Option<impl IntoIterator<Item = &'a AdditionalType> + Send + 'a>,
     ) -> ClientResult<Option<CurrentPlaybackContext>> {
         let additional_types = additional_types.map(|x| {
@@ -941,16 +1208,12 @@ pub trait OAuthClient: BaseClient {
                 .join(",")
         });
         let params = build_map([
-            ("country", country.map(Into::into)),
+            ("market", market.map(Into::into)),
             ("additional_types", additional_types.as_deref()),
         ]);
 
         let result = self.endpoint_get("me/player", &params).await?;
-        if result.is_empty() {
-            Ok(None)
-        } else {
-            convert_result(&result)
-        }
+        convert_optional_result(&result)
     }
 
     /// Get the User’s Currently Playing Track
@@ -981,11 +1244,29 @@ pub trait OAuthClient: BaseClient {
         let result = self
             .endpoint_get("me/player/currently-playing", &params)
             .await?;
-        if result.is_empty() {
-            Ok(None)
-        } else {
-            convert_result(&result)
-        }
+        convert_optional_result(&result)
+    }
+
+    /// Polls [`Self::current_playing`] every `poll_interval` and yields each
+    /// snapshot, since Spotify can't notify about playback changes. When
+    /// nothing is playing, `Ok(None)` is yielded and the polling continues, as
+    /// it does after errors. It stops once dropped.
+    ///
+    /// Parameters:
+    /// - market: Optional. an ISO 3166-1 alpha-2 country code or the string from_token.
+    /// - additional_types: Optional. The item types that your client supports
+    ///   besides the default track type.
+    /// - poll_interval: The time to wait between requests.
+    fn now_playing_stream<'a>(
+        &'a self,
+        market: Option<Market>,
+        additional_types: Option<&'a [AdditionalType]>,
+        poll_interval: time::Duration,
+    ) -> Paginator<'a, ClientResult<Option<CurrentlyPlayingContext>>> {
+        poll(
+            move || self.current_playing(market, additional_types.map(|types| types.iter())),
+            poll_interval,
+        )
     }
 
     /// Get the Current User’s Queue
@@ -1006,6 +1287,8 @@ pub trait OAuthClient: BaseClient {
     /// - device_id - transfer playback to this device
     /// - force_play - true: after transfer, play. false: keep current state.
     ///
+    /// This requires Spotify Premium.
+    ///
     /// [Reference](https://developer.spotify.com/documentation/web-api/reference/#/operations/transfer-a-users-playback)
     async fn transfer_playback(&self, device_id: &str, play: Option<bool>) -> ClientResult<()> {
         let params = JsonBuilder::new()
@@ -1031,6 +1314,8 @@ pub trait OAuthClient: BaseClient {
     /// - offset - offset into context by index or track
     /// - position_ms - Indicates from what position to start playback.
     ///
+    /// This requires Spotify Premium.
+    ///
     /// [Reference](https://developer.spotify.com/documentation/web-api/reference/#/operations/start-a-users-playback)
     async fn start_context_playback(
         &self,
@@ -1060,11 +1345,15 @@ pub trait OAuthClient: BaseClient {
     /// Start a user's playback
     ///
     /// Parameters:
-    /// - uris
+    /// - uris - for tracks obtained with a market, these should be their
+    ///   [`FullTrack::playable_id`], which is the track that is actually
+    ///   played after relinking.
     /// - device_id
     /// - offset
     /// - position_ms
     ///
+    /// This requires Spotify Premium.
+    ///
     /// [Reference](https://developer.spotify.com/documentation/web-api/reference/#/operations/start-a-users-playback)
     async fn start_uris_playback<'a>(
         &self,
@@ -1099,6 +1388,8 @@ pub trait OAuthClient: BaseClient {
     /// Parameters:
     /// - device_id - device target for playback
     ///
+    /// This requires Spotify Premium.
+    ///
     /// [Reference](https://developer.spotify.com/documentation/web-api/reference/#/operations/pause-a-users-playback)
     async fn pause_playback(&self, device_id: Option<&str>) -> ClientResult<()> {
         let url = append_device_id("me/player/pause", device_id);
@@ -1113,6 +1404,8 @@ pub trait OAuthClient: BaseClient {
     /// - device_id - device target for playback
     /// - position_ms
     ///
+    /// This requires Spotify Premium.
+    ///
     /// [Reference](https://developer.spotify.com/documentation/web-api/reference/#/operations/start-a-users-playback)
     async fn resume_playback(
         &self,
@@ -1134,6 +1427,8 @@ pub trait OAuthClient: BaseClient {
     /// Parameters:
     /// - device_id - device target for playback
     ///
+    /// This requires Spotify Premium.
+    ///
     /// [Reference](https://developer.spotify.com/documentation/web-api/reference/#/operations/skip-users-playback-to-next-track)
     async fn next_track(&self, device_id: Option<&str>) -> ClientResult<()> {
         let url = append_device_id("me/player/next", device_id);
@@ -1147,6 +1442,8 @@ pub trait OAuthClient: BaseClient {
     /// Parameters:
     /// - device_id - device target for playback
     ///
+    /// This requires Spotify Premium.
+    ///
     /// [Reference](https://developer.spotify.com/documentation/web-api/reference/#/operations/skip-users-playback-to-previous-track)
     async fn previous_track(&self, device_id: Option<&str>) -> ClientResult<()> {
         let url = append_device_id("me/player/previous", device_id);
@@ -1161,6 +1458,8 @@ pub trait OAuthClient: BaseClient {
     /// - position_ms - position in milliseconds to seek to
     /// - device_id - device target for playback
     ///
+    /// This requires Spotify Premium.
+    ///
     /// [Reference](https://developer.spotify.com/documentation/web-api/reference/#/operations/seek-to-position-in-currently-playing-track)
     async fn seek_track(&self, position_ms: u32, device_id: Option<&str>) -> ClientResult<()> {
         let url = append_device_id(
@@ -1178,6 +1477,8 @@ pub trait OAuthClient: BaseClient {
     /// - state - `track`, `context`, or `off`
     /// - device_id - device target for playback
     ///
+    /// This requires Spotify Premium.
+    ///
     /// [Reference](https://developer.spotify.com/documentation/web-api/reference/#/operations/set-repeat-mode-on-users-playback)
     async fn repeat(&self, state: RepeatState, device_id: Option<&str>) -> ClientResult<()> {
         let url = append_device_id(
@@ -1195,6 +1496,8 @@ pub trait OAuthClient: BaseClient {
     /// - volume_percent - volume between 0 and 100
     /// - device_id - device target for playback
     ///
+    /// This requires Spotify Premium.
+    ///
     /// [Reference](https://developer.spotify.com/documentation/web-api/reference/#/operations/set-volume-for-users-playback)
     async fn volume(&self, volume_percent: u8, device_id: Option<&str>) -> ClientResult<()> {
         debug_assert!(
@@ -1216,6 +1519,8 @@ pub trait OAuthClient: BaseClient {
     /// - state - true or false
     /// - device_id - device target for playback
     ///
+    /// This requires Spotify Premium.
+    ///
     /// [Reference](https://developer.spotify.com/documentation/web-api/reference/#/operations/toggle-shuffle-for-users-playback)
     async fn shuffle(&self, state: bool, device_id: Option<&str>) -> ClientResult<()> {
         let url = append_device_id(&format!("me/player/shuffle?state={state}"), device_id);
@@ -1232,6 +1537,8 @@ pub trait OAuthClient: BaseClient {
     /// - If no device ID provided the user's currently active device is
     ///   targeted
     ///
+    /// This requires Spotify Premium.
+    ///
     /// [Reference](https://developer.spotify.com/documentation/web-api/reference/#/operations/add-to-queue)
     async fn add_item_to_queue(
         &self,
@@ -1255,7 +1562,7 @@ pub trait OAuthClient: BaseClient {
         &self,
         show_ids: impl IntoIterator<Item = ShowId<'a>> + Send + 'a,
     ) -> ClientResult<()> {
-        let url = format!("me/shows/?ids={}", join_ids(show_ids));
+        let url = format!("me/shows/?ids={}", join_ids_limited(show_ids, 50)?);
         self.endpoint_put(&url, &json!({})).await?;
 
         Ok(())
@@ -1277,7 +1584,7 @@ pub trait OAuthClient: BaseClient {
     fn get_saved_show(&self) -> Paginator<'_, ClientResult<Show>> {
         paginate(
             move |limit, offset| self.get_saved_show_manual(Some(limit), Some(offset)),
-            self.get_config().pagination_chunks,
+            page_size(self.get_config(), PAGE_LIMIT),
         )
     }
 
@@ -1305,12 +1612,25 @@ pub trait OAuthClient: BaseClient {
         &self,
         ids: impl IntoIterator<Item = ShowId<'a>> + Send + 'a,
     ) -> ClientResult<Vec<bool>> {
-        let ids = join_ids(ids);
+        let ids = join_ids_limited(ids, 50)?;
         let params = build_map([("ids", Some(&ids))]);
         let result = self.endpoint_get("me/shows/contains", &params).await?;
         convert_result(&result)
     }
 
+    /// Like [`Self::check_users_saved_shows`], but returns whether each of
+    /// the given shows is saved keyed by its ID, instead of in the same order.
+    /// Duplicated IDs collapse into a single entry, so the map may have fewer
+    /// entries than the given IDs.
+    async fn check_users_saved_shows_map<'a>(
+        &self,
+        ids: impl IntoIterator<Item = ShowId<'a>> + Send + 'a,
+    ) -> ClientResult<HashMap<ShowId<'a>, bool>> {
+        let ids = ids.into_iter().collect::<Vec<_>>();
+        let saved = self.check_users_saved_shows(ids.clone()).await?;
+        Ok(ids.into_iter().zip(saved).collect())
+    }
+
     /// Delete one or more shows from current Spotify user's library.
     /// Changes to a user's saved shows may not be visible in other Spotify applications immediately.
     ///
@@ -1322,14 +1642,337 @@ pub trait OAuthClient: BaseClient {
     async fn remove_users_saved_shows<'a>(
         &self,
         show_ids: impl IntoIterator<Item = ShowId<'a>> + Send + 'a,
-        country: Option<Market>,
+        market: Option<Market>,
     ) -> ClientResult<()> {
-        let url = format!("me/shows?ids={}", join_ids(show_ids));
-        let params = JsonBuilder::new()
-            .optional("country", country.map(<&str>::from))
-            .build();
-        self.endpoint_delete(&url, &params).await?;
+        let mut url = format!("me/shows?ids={}", join_ids_limited(show_ids, 50)?);
+        if let Some(market) = market {
+            url.push_str("&market=");
+            url.push_str(market.into());
+        }
+        self.endpoint_delete(&url, &json!({})).await?;
+
+        Ok(())
+    }
+
+    /// Save one or more episodes to the current Spotify user's library.
+    ///
+    /// Parameters:
+    /// - ids(Required) A comma-separated list of Spotify IDs for the episodes
+    ///   to be added to the user's library. Maximum: 50 IDs.
+    ///
+    /// [Reference](https://developer.spotify.com/documentation/web-api/reference/#/operations/save-episodes-user)
+    async fn save_episodes<'a>(
+        &self,
+        episode_ids: impl IntoIterator<Item = EpisodeId<'a>> + Send + 'a,
+    ) -> ClientResult<()> {
+        let url = format!("me/episodes?ids={}", join_ids_limited(episode_ids, 50)?);
+        self.endpoint_put(&url, &json!({})).await?;
+
+        Ok(())
+    }
+
+    /// Get a list of the episodes saved in the current Spotify user's library.
+    ///
+    /// Parameters:
+    /// - market(Optional). An ISO 3166-1 alpha-2 country code or the string
+    ///   from_token.
+    /// - limit(Optional). The maximum number of episodes to return.
+    ///   Default: 20. Minimum: 1. Maximum: 50.
+    /// - offset(Optional). The index of the first episode to return.
+    ///   Default: 0 (the first object). Use with limit to get the next set of
+    ///   episodes.
+    ///
+    /// See [`Self::current_user_saved_episodes_manual`] for a manually
+    /// paginated version of this.
+    ///
+    /// [Reference](https://developer.spotify.com/documentation/web-api/reference/#/operations/get-users-saved-episodes)
+    fn current_user_saved_episodes(
+        &self,
+        market: Option<Market>,
+    ) -> Paginator<'_, ClientResult<SavedEpisode>> {
+        paginate(
+            move |limit, offset| {
+                self.current_user_saved_episodes_manual(market, Some(limit), Some(offset))
+            },
+            page_size(self.get_config(), PAGE_LIMIT),
+        )
+    }
+
+    /// The manually paginated version of
+    /// [`Self::current_user_saved_episodes`].
+    async fn current_user_saved_episodes_manual(
+        &self,
+        market: Option<Market>,
+        limit: Option<u32>,
+        offset: Option<u32>,
+    ) -> ClientResult<Page<SavedEpisode>> {
+        let limit = limit.map(|x| x.to_string());
+        let offset = offset.map(|x| x.to_string());
+        let params = build_map([
+            ("market", market.map(Into::into)),
+            ("limit", limit.as_deref()),
+            ("offset", offset.as_deref()),
+        ]);
+
+        let result = self.endpoint_get("me/episodes", &params).await?;
+        convert_result(&result)
+    }
+
+    /// Check if one or more episodes are already saved in the current Spotify
+    /// user's library.
+    ///
+    /// Query Parameters
+    /// - ids: Required. A comma-separated list of the Spotify IDs for the episodes. Maximum: 50 IDs.
+    ///
+    /// [Reference](https://developer.spotify.com/documentation/web-api/reference/#/operations/check-users-saved-episodes)
+    async fn check_users_saved_episodes<'a>(
+        &self,
+        ids: impl IntoIterator<Item = EpisodeId<'a>> + Send + 'a,
+    ) -> ClientResult<Vec<bool>> {
+        let ids = join_ids_limited(ids, 50)?;
+        let params = build_map([("ids", Some(&ids))]);
+        let result = self.endpoint_get("me/episodes/contains", &params).await?;
+        convert_result(&result)
+    }
+
+    /// Remove one or more episodes from the current Spotify user's library.
+    ///
+    /// Query Parameters
+    /// - ids: Required. A comma-separated list of Spotify IDs for the episodes to be deleted from the user's library. Maximum: 50 IDs.
+    ///
+    /// [Reference](https://developer.spotify.com/documentation/web-api/reference/#/operations/remove-episodes-user)
+    async fn remove_saved_episodes<'a>(
+        &self,
+        episode_ids: impl IntoIterator<Item = EpisodeId<'a>> + Send + 'a,
+    ) -> ClientResult<()> {
+        let url = format!("me/episodes?ids={}", join_ids_limited(episode_ids, 50)?);
+        self.endpoint_delete(&url, &json!({})).await?;
+
+        Ok(())
+    }
+
+    /// Save one or more audiobooks to the current Spotify user's library.
+    ///
+    /// Parameters:
+    /// - ids(Required) A comma-separated list of Spotify IDs for the
+    ///   audiobooks to be added to the user's library. Maximum: 50 IDs.
+    ///
+    /// [Reference](https://developer.spotify.com/documentation/web-api/reference/#/operations/save-audiobooks-user)
+    async fn save_audiobooks<'a>(
+        &self,
+        audiobook_ids: impl IntoIterator<Item = AudiobookId<'a>> + Send + 'a,
+    ) -> ClientResult<()> {
+        let url = format!("me/audiobooks?ids={}", join_ids_limited(audiobook_ids, 50)?);
+        self.endpoint_put(&url, &json!({})).await?;
+
+        Ok(())
+    }
+
+    /// Get a list of the audiobooks saved in the current Spotify user's
+    /// library.
+    ///
+    /// Parameters:
+    /// - limit(Optional). The maximum number of audiobooks to return.
+    ///   Default: 20. Minimum: 1. Maximum: 50.
+    /// - offset(Optional). The index of the first audiobook to return.
+    ///   Default: 0 (the first object). Use with limit to get the next set of
+    ///   audiobooks.
+    ///
+    /// See [`Self::current_user_saved_audiobooks_manual`] for a manually
+    /// paginated version of this.
+    ///
+    /// [Reference](https://developer.spotify.com/documentation/web-api/reference/#/operations/get-users-saved-audiobooks)
+    fn current_user_saved_audiobooks(&self) -> Paginator<'_, ClientResult<SimplifiedAudiobook>> {
+        paginate(
+            move |limit, offset| {
+                self.current_user_saved_audiobooks_manual(Some(limit), Some(offset))
+            },
+            page_size(self.get_config(), PAGE_LIMIT),
+        )
+    }
+
+    /// The manually paginated version of
+    /// [`Self::current_user_saved_audiobooks`].
+    async fn current_user_saved_audiobooks_manual(
+        &self,
+        limit: Option<u32>,
+        offset: Option<u32>,
+    ) -> ClientResult<Page<SimplifiedAudiobook>> {
+        let limit = limit.map(|x| x.to_string());
+        let offset = offset.map(|x| x.to_string());
+        let params = build_map([("limit", limit.as_deref()), ("offset", offset.as_deref())]);
+
+        let result = self.endpoint_get("me/audiobooks", &params).await?;
+        convert_result(&result)
+    }
+
+    /// Check if one or more audiobooks are already saved in the current
+    /// Spotify user's library.
+    ///
+    /// Query Parameters
+    /// - ids: Required. A comma-separated list of the Spotify IDs for the audiobooks. Maximum: 50 IDs.
+    ///
+    /// [Reference](https://developer.spotify.com/documentation/web-api/reference/#/operations/check-users-saved-audiobooks)
+    async fn check_users_saved_audiobooks<'a>(
+        &self,
+        ids: impl IntoIterator<Item = AudiobookId<'a>> + Send + 'a,
+    ) -> ClientResult<Vec<bool>> {
+        let ids = join_ids_limited(ids, 50)?;
+        let params = build_map([("ids", Some(&ids))]);
+        let result = self.endpoint_get("me/audiobooks/contains", &params).await?;
+        convert_result(&result)
+    }
+
+    /// Remove one or more audiobooks from the current Spotify user's library.
+    ///
+    /// Query Parameters
+    /// - ids: Required. A comma-separated list of Spotify IDs for the audiobooks to be deleted from the user's library. Maximum: 50 IDs.
+    ///
+    /// [Reference](https://developer.spotify.com/documentation/web-api/reference/#/operations/remove-audiobooks-user)
+    async fn delete_saved_audiobooks<'a>(
+        &self,
+        audiobook_ids: impl IntoIterator<Item = AudiobookId<'a>> + Send + 'a,
+    ) -> ClientResult<()> {
+        let url = format!("me/audiobooks?ids={}", join_ids_limited(audiobook_ids, 50)?);
+        self.endpoint_delete(&url, &json!({})).await?;
 
         Ok(())
     }
 }
+
+/// Loads the cached token into the client, refreshing it if it's expired, or
+/// obtains a new one with the code from `get_code` otherwise. This is the
+/// shared logic of [`OAuthClient::prompt_for_token`] and
+/// [`OAuthClient::prompt_for_token_local_server`].
+#[cfg(feature = "cli")]
+#[maybe_async]
+async fn prompt_for_token_with<C, F>(client: &C, url: &str, get_code: F) -> ClientResult<()>
+where
+    C: OAuthClient,
+    F: Fn(&C, &str) -> ClientResult<String> + Send + Sync,
+{
+    match client.read_token_cache(true).await {
+        Ok(Some(new_token)) => {
+            let expired = new_token.is_expired_with_skew(client.get_config().token_expiry_skew);
+
+            // Load token into client regardless of whether it's expired o
+            // not, since it will be refreshed later anyway.
+            *client.get_token().lock().await.unwrap() = Some(new_token);
+
+            if expired {
+                // Ensure that we actually got a token from the refetch
+                match client.refetch_token().await? {
+                    Some(refreshed_token) => {
+                        log::info!("Successfully refreshed expired token from token cache");
+                        *client.get_token().lock().await.unwrap() = Some(refreshed_token)
+                    }
+                    // If not, prompt the user for it
+                    None => {
+                        log::info!("Unable to refresh expired token from token cache");
+                        let code = get_code(client, url)?;
+                        client.request_token(&code).await?;
+                    }
+                }
+            }
+        }
+        // Otherwise following the usual procedure to get the token.
+        _ => {
+            let code = get_code(client, url)?;
+            client.request_token(&code).await?;
+        }
+    }
+
+    client.write_token_cache().await
+}
+
+/// Tries to open the given URL in the user's browser, or asks them to do it
+/// manually if that fails.
+#[cfg(feature = "cli")]
+fn open_in_browser(url: &str) {
+    log::info!("Opening brower with auth URL");
+    match webbrowser::open(url) {
+        Ok(_) => println!("Opened {} in your browser.", url),
+        Err(why) => eprintln!(
+            "Error when trying to open an URL in your browser: {:?}. \
+             Please navigate here manually: {}",
+            why, url
+        ),
+    }
+}
+
+/// The address to listen on for the callback, if the redirect URI is a local
+/// HTTP one.
+#[cfg(feature = "cli")]
+fn local_server_addr(redirect_uri: &Url) -> Option<(&str, u16)> {
+    let host = redirect_uri.host_str()?;
+    let is_local = matches!(host, "localhost" | "127.0.0.1" | "[::1]");
+    if redirect_uri.scheme() != "http" || !is_local {
+        return None;
+    }
+
+    Some((
+        host.trim_start_matches('[').trim_end_matches(']'),
+        redirect_uri.port_or_known_default()?,
+    ))
+}
+
+/// Builds the full callback URL from the request line of an HTTP request
+/// (e.g. `GET /callback?code=...&state=... HTTP/1.1`), if it's a callback to
+/// the redirect URI.
+#[cfg(feature = "cli")]
+fn callback_url(redirect_uri: &Url, request_line: &str) -> Option<Url> {
+    let mut parts = request_line.split_whitespace();
+    if parts.next()? != "GET" {
+        return None;
+    }
+
+    let callback = redirect_uri.join(parts.next()?).ok()?;
+    if callback.path() != redirect_uri.path() {
+        return None;
+    }
+
+    Some(callback)
+}
+
+#[cfg(all(test, feature = "cli"))]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_local_server_addr() {
+        let url = Url::parse("http://localhost:8888/callback").unwrap();
+        assert_eq!(local_server_addr(&url), Some(("localhost", 8888)));
+        let url = Url::parse("http://[::1]/callback").unwrap();
+        assert_eq!(local_server_addr(&url), Some(("::1", 80)));
+
+        let url = Url::parse("https://localhost:8888/callback").unwrap();
+        assert_eq!(local_server_addr(&url), None);
+        let url = Url::parse("http://example.com/callback").unwrap();
+        assert_eq!(local_server_addr(&url), None);
+    }
+
+    #[test]
+    fn test_callback_url() {
+        let redirect_uri = Url::parse("http://localhost:8888/callback").unwrap();
+
+        let callback = callback_url(
+            &redirect_uri,
+            "GET /callback?code=abc&state=xyz HTTP/1.1\r\n",
+        )
+        .unwrap();
+        assert_eq!(
+            callback.as_str(),
+            "http://localhost:8888/callback?code=abc&state=xyz"
+        );
+
+        assert_eq!(
+            callback_url(&redirect_uri, "GET /favicon.ico HTTP/1.1\r\n"),
+            None
+        );
+        assert_eq!(
+            callback_url(&redirect_uri, "POST /callback?code=abc HTTP/1.1\r\n"),
+            None
+        );
+        assert_eq!(callback_url(&redirect_uri, ""), None);
+    }
+}
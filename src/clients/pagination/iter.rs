@@ -1,10 +1,25 @@
 //! Synchronous implementation of automatic pagination requests.
 
-use crate::{model::Page, ClientError, ClientResult};
+use super::PaginatorExt;
+use crate::{
+    model::{Cursor, CursorBasedPage, Page},
+    ClientError, ClientResult,
+};
+
+use std::{iter, thread, time::Duration};
+
+use maybe_async::sync_impl;
 
 /// Alias for `Iterator<Item = T>`, since sync mode is enabled.
 pub type Paginator<'a, T> = Box<dyn Iterator<Item = T> + 'a>;
 
+#[sync_impl]
+impl<'a, T> PaginatorExt<T> for Paginator<'a, ClientResult<T>> {
+    fn collect_all(self) -> ClientResult<Vec<T>> {
+        self.collect()
+    }
+}
+
 pub fn paginate_with_ctx<'a, Ctx: 'a, T: 'a, Request: 'a>(
     ctx: Ctx,
     req: Request,
@@ -21,12 +36,80 @@ pub fn paginate<'a, T: 'a, Request: 'a>(
     req: Request,
     page_size: u32,
 ) -> Paginator<'a, ClientResult<T>>
+where
+    Request: Fn(u32, u32) -> ClientResult<Page<T>>,
+{
+    paginate_from(req, page_size, 0)
+}
+
+/// Same as [`paginate_with_ctx`], but starting at `start_offset` instead of the
+/// first item. This makes it possible to resume a pagination that was
+/// interrupted without fetching the previous pages again.
+pub fn paginate_with_ctx_from<'a, Ctx: 'a, T: 'a, Request: 'a>(
+    ctx: Ctx,
+    req: Request,
+    page_size: u32,
+    start_offset: u32,
+) -> Paginator<'a, ClientResult<T>>
+where
+    Request: Fn(&Ctx, u32, u32) -> ClientResult<Page<T>>,
+{
+    paginate_from(
+        move |limit, offset| req(&ctx, limit, offset),
+        page_size,
+        start_offset,
+    )
+}
+
+/// Same as [`paginate`], but starting at `start_offset` instead of the first
+/// item. This makes it possible to resume a pagination that was interrupted
+/// without fetching the previous pages again.
+pub fn paginate_from<'a, T: 'a, Request: 'a>(
+    req: Request,
+    page_size: u32,
+    start_offset: u32,
+) -> Paginator<'a, ClientResult<T>>
 where
     Request: Fn(u32, u32) -> ClientResult<Page<T>>,
 {
     let pages = PageIterator {
         req,
-        offset: 0,
+        offset: start_offset,
+        done: false,
+        page_size,
+    };
+
+    Box::new(pages.flat_map(|result| ResultIter::new(result.map(|page| page.items.into_iter()))))
+}
+
+/// Same as [`paginate`], since requests can't be kept in flight concurrently in
+/// synchronous mode. It's only available for compatibility with the
+/// asynchronous implementation, where up to `lookahead` pages are prefetched.
+pub fn paginate_buffered<'a, T: 'a, Request: 'a>(
+    req: Request,
+    page_size: u32,
+    _lookahead: usize,
+) -> Paginator<'a, ClientResult<T>>
+where
+    Request: Fn(u32, u32) -> ClientResult<Page<T>>,
+{
+    paginate(req, page_size)
+}
+
+/// This is used to handle cursor-based paginated requests automatically. The
+/// cursors of each page are passed to the request of the next one, which
+/// should choose the relevant one, and the pagination stops once there are no
+/// more pages.
+pub fn paginate_cursor<'a, T: 'a, Request: 'a>(
+    req: Request,
+    page_size: u32,
+) -> Paginator<'a, ClientResult<T>>
+where
+    Request: Fn(u32, Option<Cursor>) -> ClientResult<CursorBasedPage<T>>,
+{
+    let pages = CursorPageIterator {
+        req,
+        cursors: None,
         done: false,
         page_size,
     };
@@ -34,8 +117,102 @@ where
     Box::new(pages.flat_map(|result| ResultIter::new(result.map(|page| page.items.into_iter()))))
 }
 
-/// Iterator that repeatedly calls a function that returns a page until an empty
-/// page is returned.
+/// Repeats a request forever, waiting `interval` between them, and yields
+/// every result, including the errors. This is useful to watch for changes in
+/// endpoints that can't notify about them, and it stops once dropped.
+pub fn poll<'a, T: 'a, Request: 'a>(
+    req: Request,
+    interval: Duration,
+) -> Paginator<'a, ClientResult<T>>
+where
+    Request: Fn() -> ClientResult<T>,
+{
+    let mut first = true;
+    Box::new(iter::from_fn(move || {
+        if !first {
+            thread::sleep(interval);
+        }
+        first = false;
+        Some(req())
+    }))
+}
+
+/// Same as [`paginate_with_ctx`], but yielding the whole pages instead of their
+/// items, so that fields like `total` are available as well.
+pub fn paginate_pages_with_ctx<'a, Ctx: 'a, T: 'a, Request: 'a>(
+    ctx: Ctx,
+    req: Request,
+    page_size: u32,
+) -> Paginator<'a, ClientResult<Page<T>>>
+where
+    Request: Fn(&Ctx, u32, u32) -> ClientResult<Page<T>>,
+{
+    paginate_pages(move |limit, offset| req(&ctx, limit, offset), page_size)
+}
+
+/// Same as [`paginate`], but yielding the whole pages instead of their items,
+/// so that fields like `total` are available as well.
+pub fn paginate_pages<'a, T: 'a, Request: 'a>(
+    req: Request,
+    page_size: u32,
+) -> Paginator<'a, ClientResult<Page<T>>>
+where
+    Request: Fn(u32, u32) -> ClientResult<Page<T>>,
+{
+    Box::new(PageIterator {
+        req,
+        offset: 0,
+        done: false,
+        page_size,
+    })
+}
+
+/// Same as [`paginate_with_ctx`], but walking the pages backwards, starting at
+/// `start_offset` and ending with the first page. Both the pages and their
+/// items are yielded in reverse order, which is useful for tail-first views,
+/// e.g., the most recently added tracks of a playlist first.
+pub fn paginate_with_ctx_rev<'a, Ctx: 'a, T: 'a, Request: 'a>(
+    ctx: Ctx,
+    req: Request,
+    page_size: u32,
+    start_offset: u32,
+) -> Paginator<'a, ClientResult<T>>
+where
+    Request: Fn(&Ctx, u32, u32) -> ClientResult<Page<T>>,
+{
+    paginate_rev(
+        move |limit, offset| req(&ctx, limit, offset),
+        page_size,
+        start_offset,
+    )
+}
+
+/// Same as [`paginate`], but walking the pages backwards, starting at
+/// `start_offset` and ending with the first page. Both the pages and their
+/// items are yielded in reverse order, which is useful for tail-first views,
+/// e.g., the most recently added tracks of a playlist first.
+pub fn paginate_rev<'a, T: 'a, Request: 'a>(
+    req: Request,
+    page_size: u32,
+    start_offset: u32,
+) -> Paginator<'a, ClientResult<T>>
+where
+    Request: Fn(u32, u32) -> ClientResult<Page<T>>,
+{
+    let pages = RevPageIterator {
+        req,
+        limit: page_size,
+        offset: Some(start_offset),
+        page_size,
+    };
+
+    Box::new(
+        pages.flat_map(|result| ResultIter::new(result.map(|page| page.items.into_iter().rev()))),
+    )
+}
+
+/// Iterator that repeatedly calls a function that returns a page until the last
+/// or an empty page is returned.
 struct PageIterator<Request> {
     req: Request,
     offset: u32,
@@ -61,6 +238,79 @@ where
             }
             Ok(page) => {
                 self.offset += page.items.len() as u32;
+                self.done = page.next.is_none();
+                Some(Ok(page))
+            }
+            Err(e) => Some(Err(e)),
+        }
+    }
+}
+
+/// Iterator that repeatedly calls a function that returns a page, going
+/// backwards until the first page is reached.
+struct RevPageIterator<Request> {
+    req: Request,
+    limit: u32,
+    /// `None` once the first page has been returned
+    offset: Option<u32>,
+    page_size: u32,
+}
+
+impl<T, Request> Iterator for RevPageIterator<Request>
+where
+    Request: Fn(u32, u32) -> ClientResult<Page<T>>,
+{
+    type Item = ClientResult<Page<T>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let offset = self.offset?;
+
+        match (self.req)(self.limit, offset) {
+            Ok(page) => {
+                if offset == 0 || page.previous.is_none() {
+                    self.offset = None;
+                } else {
+                    // The first page is clamped so that it never requests a
+                    // negative offset, nor items that were already returned.
+                    match offset.checked_sub(self.page_size) {
+                        Some(previous) => self.offset = Some(previous),
+                        None => {
+                            self.limit = offset;
+                            self.offset = Some(0);
+                        }
+                    }
+                }
+                Some(Ok(page))
+            }
+            Err(e) => Some(Err(e)),
+        }
+    }
+}
+
+/// Iterator that repeatedly calls a function that returns a cursor-based page,
+/// with the cursors of the previous one, until the last page is returned.
+struct CursorPageIterator<Request> {
+    req: Request,
+    cursors: Option<Cursor>,
+    done: bool,
+    page_size: u32,
+}
+
+impl<T, Request> Iterator for CursorPageIterator<Request>
+where
+    Request: Fn(u32, Option<Cursor>) -> ClientResult<CursorBasedPage<T>>,
+{
+    type Item = ClientResult<CursorBasedPage<T>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+
+        match (self.req)(self.page_size, self.cursors.take()) {
+            Ok(mut page) => {
+                self.cursors = page.cursors.take();
+                self.done = page.next.is_none() || self.cursors.is_none();
                 Some(Ok(page))
             }
             Err(e) => Some(Err(e)),
@@ -11,6 +11,18 @@
 //!   function, but accepts a generic context that works around lifetime issues
 //!   in the async version due to restrictions in HRTBs
 //!   (<https://kevincox.ca/2022/04/16/rust-generic-closure-lifetimes/>)
+//! * `paginate_pages` and `paginate_pages_with_ctx` functions, which yield the
+//!   whole `Page`s instead of their items
+//! * `paginate_from` and `paginate_with_ctx_from` functions, which start at a
+//!   given offset, so that an interrupted pagination may be resumed
+//! * A `paginate_cursor` function, which does the same for cursor-based pages
+//! * A `paginate_buffered` function, which prefetches the following pages in the
+//!   async version
+//! * A `PaginatorExt` trait with convenience methods for the `Paginator`
+//! * `paginate_rev` and `paginate_with_ctx_rev` functions, which walk the pages
+//!   backwards from a starting offset
+//! * A `poll` function, which isn't about pagination but repeats a request on
+//!   an interval, yielding every result in the same kind of `Paginator`
 //!
 //! Note that `Paginator` should actually be a trait so that a dynamic
 //! allocation can be avoided when returning it with `-> impl Iterator<T>`, as
@@ -23,12 +35,133 @@
 //! pagination may borrow the client itself in order to make requests, and said
 //! lifetime helps ensure the `Paginator` struct won't outlive the client.
 
+use crate::ClientResult;
+
+use maybe_async::maybe_async;
+
 #[cfg(feature = "__sync")]
 mod iter;
 #[cfg(feature = "__async")]
 mod stream;
 
 #[cfg(feature = "__sync")]
-pub use iter::{paginate, paginate_with_ctx, Paginator};
+pub use iter::{
+    paginate, paginate_buffered, paginate_cursor, paginate_from, paginate_pages,
+    paginate_pages_with_ctx, paginate_rev, paginate_with_ctx, paginate_with_ctx_from,
+    paginate_with_ctx_rev, poll, Paginator,
+};
 #[cfg(feature = "__async")]
-pub use stream::{paginate, paginate_with_ctx, Paginator};
+pub use stream::{
+    paginate, paginate_buffered, paginate_cursor, paginate_from, paginate_pages,
+    paginate_pages_with_ctx, paginate_rev, paginate_with_ctx, paginate_with_ctx_from,
+    paginate_with_ctx_rev, poll, Paginator,
+};
+
+/// Convenience methods for [`Paginator`], which is only a type alias and thus
+/// can't have inherent methods.
+#[maybe_async(?Send)]
+pub trait PaginatorExt<T> {
+    /// Drives the paginator to completion, returning either all of its items
+    /// or the first error found, after which no more requests are made.
+    ///
+    /// Note that this performs a request for every page, which may add up to
+    /// lots of them for large collections, like the saved tracks of a user.
+    async fn collect_all(self) -> ClientResult<Vec<T>>;
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::model::{Cursor, CursorBasedPage, Page};
+
+    const TOTAL: u32 = 95;
+    const PAGE_SIZE: u32 = 30;
+
+    /// Simulates a paginated endpoint whose items are their own offset.
+    #[maybe_async]
+    async fn request(limit: u32, offset: u32) -> ClientResult<Page<u32>> {
+        let end = TOTAL.min(offset + limit);
+        Ok(Page {
+            items: (offset..end).collect(),
+            limit,
+            next: (end < TOTAL).then(|| "next".to_owned()),
+            offset,
+            previous: (offset > 0).then(|| "previous".to_owned()),
+            total: TOTAL,
+            ..Default::default()
+        })
+    }
+
+    /// Simulates a cursor-based paginated endpoint whose cursor is the last
+    /// item returned.
+    #[maybe_async]
+    async fn cursor_request(
+        limit: u32,
+        cursors: Option<Cursor>,
+    ) -> ClientResult<CursorBasedPage<u32>> {
+        let start = cursors
+            .and_then(|cursors| cursors.after)
+            .map_or(0, |after| after.parse::<u32>().unwrap() + 1);
+        let end = TOTAL.min(start + limit);
+        Ok(CursorBasedPage {
+            items: (start..end).collect(),
+            limit,
+            next: (end < TOTAL).then(|| "next".to_owned()),
+            cursors: Some(Cursor {
+                after: (end < TOTAL).then(|| (end - 1).to_string()),
+                before: None,
+            }),
+            ..Default::default()
+        })
+    }
+
+    #[maybe_async::test(feature = "__sync", async(feature = "__async", tokio::test))]
+    async fn test_paginate() {
+        let items = paginate(request, PAGE_SIZE).collect_all().await.unwrap();
+        assert_eq!(items, (0..TOTAL).collect::<Vec<_>>());
+
+        let pages = paginate_pages(request, PAGE_SIZE)
+            .collect_all()
+            .await
+            .unwrap();
+        assert_eq!(pages.len(), 4);
+        assert!(pages.iter().all(|page| page.total == TOTAL));
+    }
+
+    #[maybe_async::test(feature = "__sync", async(feature = "__async", tokio::test))]
+    async fn test_paginate_from() {
+        let items = paginate_from(request, PAGE_SIZE, 40)
+            .collect_all()
+            .await
+            .unwrap();
+        assert_eq!(items, (40..TOTAL).collect::<Vec<_>>());
+    }
+
+    #[maybe_async::test(feature = "__sync", async(feature = "__async", tokio::test))]
+    async fn test_paginate_rev() {
+        // The first page is clamped, so that no item is returned twice
+        let items = paginate_rev(request, PAGE_SIZE, 80)
+            .collect_all()
+            .await
+            .unwrap();
+        assert_eq!(items, (0..TOTAL).rev().collect::<Vec<_>>());
+    }
+
+    #[maybe_async::test(feature = "__sync", async(feature = "__async", tokio::test))]
+    async fn test_paginate_cursor() {
+        let items = paginate_cursor(cursor_request, PAGE_SIZE)
+            .collect_all()
+            .await
+            .unwrap();
+        assert_eq!(items, (0..TOTAL).collect::<Vec<_>>());
+    }
+
+    #[maybe_async::test(feature = "__sync", async(feature = "__async", tokio::test))]
+    async fn test_paginate_buffered() {
+        let items = paginate_buffered(request, PAGE_SIZE, 3)
+            .collect_all()
+            .await
+            .unwrap();
+        assert_eq!(items, (0..TOTAL).collect::<Vec<_>>());
+    }
+}
@@ -1,14 +1,29 @@
 //! Asynchronous implementation of automatic pagination requests.
 
-use crate::{model::Page, ClientResult};
+use super::PaginatorExt;
+use crate::{
+    model::{Cursor, CursorBasedPage, Page},
+    ClientResult,
+};
 
-use std::pin::Pin;
+use std::{pin::Pin, time::Duration};
 
-use futures::{future::Future, stream::Stream};
+use futures::{
+    future::Future,
+    stream::{self, Stream, StreamExt, TryStreamExt},
+};
+use maybe_async::async_impl;
 
 /// Alias for `futures::stream::Stream<Item = T>`, since async mode is enabled.
 pub type Paginator<'a, T> = Pin<Box<dyn Stream<Item = T> + 'a>>;
 
+#[async_impl(?Send)]
+impl<'a, T> PaginatorExt<T> for Paginator<'a, ClientResult<T>> {
+    async fn collect_all(self) -> ClientResult<Vec<T>> {
+        self.try_collect().await
+    }
+}
+
 pub type RequestFuture<'a, T> = Pin<Box<dyn 'a + Future<Output = ClientResult<Page<T>>>>>;
 
 /// This is used to handle paginated requests automatically.
@@ -20,41 +35,300 @@ pub fn paginate_with_ctx<'a, Ctx: 'a, T, Request>(
 where
     T: 'a + Unpin,
     Request: 'a + for<'ctx> Fn(&'ctx Ctx, u32, u32) -> RequestFuture<'ctx, T>,
+{
+    flatten_pages(paginate_pages_with_ctx(ctx, req, page_size))
+}
+
+pub fn paginate<'a, T, Fut, Request>(req: Request, page_size: u32) -> Paginator<'a, ClientResult<T>>
+where
+    T: 'a + Unpin,
+    Fut: Future<Output = ClientResult<Page<T>>>,
+    Request: 'a + Fn(u32, u32) -> Fut,
+{
+    flatten_pages(paginate_pages(req, page_size))
+}
+
+/// Same as [`paginate_with_ctx`], but starting at `start_offset` instead of the
+/// first item. This makes it possible to resume a pagination that was
+/// interrupted without fetching the previous pages again.
+pub fn paginate_with_ctx_from<'a, Ctx: 'a, T, Request>(
+    ctx: Ctx,
+    req: Request,
+    page_size: u32,
+    start_offset: u32,
+) -> Paginator<'a, ClientResult<T>>
+where
+    T: 'a + Unpin,
+    Request: 'a + for<'ctx> Fn(&'ctx Ctx, u32, u32) -> RequestFuture<'ctx, T>,
+{
+    flatten_pages(pages_with_ctx_from(ctx, req, page_size, start_offset))
+}
+
+/// Same as [`paginate`], but starting at `start_offset` instead of the first
+/// item. This makes it possible to resume a pagination that was interrupted
+/// without fetching the previous pages again.
+pub fn paginate_from<'a, T, Fut, Request>(
+    req: Request,
+    page_size: u32,
+    start_offset: u32,
+) -> Paginator<'a, ClientResult<T>>
+where
+    T: 'a + Unpin,
+    Fut: Future<Output = ClientResult<Page<T>>>,
+    Request: 'a + Fn(u32, u32) -> Fut,
+{
+    flatten_pages(pages_from(req, page_size, start_offset))
+}
+
+/// Same as [`paginate`], but keeping up to `lookahead` page requests in flight,
+/// so that the next pages are already being fetched while the current one is
+/// consumed. The order of the items is preserved.
+///
+/// Since the offsets are requested before knowing where the collection ends,
+/// this may over-fetch by up to `lookahead` pages past the last one.
+pub fn paginate_buffered<'a, T, Fut, Request>(
+    req: Request,
+    page_size: u32,
+    lookahead: usize,
+) -> Paginator<'a, ClientResult<T>>
+where
+    T: 'a + Unpin,
+    Fut: 'a + Future<Output = ClientResult<Page<T>>>,
+    Request: 'a + Fn(u32, u32) -> Fut,
 {
     use async_stream::stream;
-    let mut offset = 0;
+    let pages = stream::iter(0u32..)
+        .map(move |page| req(page_size, page.saturating_mul(page_size)))
+        .buffered(lookahead.max(1));
+    Box::pin(stream! {
+        for await page in pages {
+            let page = page?;
+            let last = page.next.is_none();
+            for item in page.items {
+                yield Ok(item);
+            }
+            if last {
+                break;
+            }
+        }
+    })
+}
+
+/// This is used to handle cursor-based paginated requests automatically. The
+/// cursors of each page are passed to the request of the next one, which
+/// should choose the relevant one, and the pagination stops once there are no
+/// more pages.
+pub fn paginate_cursor<'a, T, Fut, Request>(
+    req: Request,
+    page_size: u32,
+) -> Paginator<'a, ClientResult<T>>
+where
+    T: 'a + Unpin,
+    Fut: Future<Output = ClientResult<CursorBasedPage<T>>>,
+    Request: 'a + Fn(u32, Option<Cursor>) -> Fut,
+{
+    use async_stream::stream;
+    let mut cursors = None;
     Box::pin(stream! {
         loop {
-            let page = req(&ctx, page_size, offset).await?;
-            offset += page.items.len() as u32;
+            let page = req(page_size, cursors.take()).await?;
+            let last = page.next.is_none();
+            cursors = page.cursors;
             for item in page.items {
                 yield Ok(item);
             }
-            if page.next.is_none() {
+            if last || cursors.is_none() {
                 break;
             }
         }
     })
 }
 
-pub fn paginate<'a, T, Fut, Request>(req: Request, page_size: u32) -> Paginator<'a, ClientResult<T>>
+/// Repeats a request forever, waiting `interval` between them, and yields
+/// every result, including the errors. This is useful to watch for changes in
+/// endpoints that can't notify about them, and it stops once dropped.
+pub fn poll<'a, T, Fut, Request>(req: Request, interval: Duration) -> Paginator<'a, ClientResult<T>>
+where
+    T: 'a,
+    Fut: Future<Output = ClientResult<T>>,
+    Request: 'a + Fn() -> Fut,
+{
+    use async_stream::stream;
+    Box::pin(stream! {
+        loop {
+            yield req().await;
+            crate::http::__private::sleep(interval).await;
+        }
+    })
+}
+
+/// Same as [`paginate_with_ctx`], but yielding the whole pages instead of their
+/// items, so that fields like `total` are available as well.
+pub fn paginate_pages_with_ctx<'a, Ctx: 'a, T, Request>(
+    ctx: Ctx,
+    req: Request,
+    page_size: u32,
+) -> Paginator<'a, ClientResult<Page<T>>>
+where
+    T: 'a + Unpin,
+    Request: 'a + for<'ctx> Fn(&'ctx Ctx, u32, u32) -> RequestFuture<'ctx, T>,
+{
+    pages_with_ctx_from(ctx, req, page_size, 0)
+}
+
+/// Same as [`paginate`], but yielding the whole pages instead of their items,
+/// so that fields like `total` are available as well.
+pub fn paginate_pages<'a, T, Fut, Request>(
+    req: Request,
+    page_size: u32,
+) -> Paginator<'a, ClientResult<Page<T>>>
 where
     T: 'a + Unpin,
     Fut: Future<Output = ClientResult<Page<T>>>,
     Request: 'a + Fn(u32, u32) -> Fut,
+{
+    pages_from(req, page_size, 0)
+}
+
+fn pages_with_ctx_from<'a, Ctx: 'a, T, Request>(
+    ctx: Ctx,
+    req: Request,
+    page_size: u32,
+    start_offset: u32,
+) -> Paginator<'a, ClientResult<Page<T>>>
+where
+    T: 'a + Unpin,
+    Request: 'a + for<'ctx> Fn(&'ctx Ctx, u32, u32) -> RequestFuture<'ctx, T>,
 {
     use async_stream::stream;
-    let mut offset = 0;
+    let mut offset = start_offset;
+    Box::pin(stream! {
+        loop {
+            let page = req(&ctx, page_size, offset).await?;
+            offset += page.items.len() as u32;
+            let last = page.next.is_none();
+            yield Ok(page);
+            if last {
+                break;
+            }
+        }
+    })
+}
+
+fn pages_from<'a, T, Fut, Request>(
+    req: Request,
+    page_size: u32,
+    start_offset: u32,
+) -> Paginator<'a, ClientResult<Page<T>>>
+where
+    T: 'a + Unpin,
+    Fut: Future<Output = ClientResult<Page<T>>>,
+    Request: 'a + Fn(u32, u32) -> Fut,
+{
+    use async_stream::stream;
+    let mut offset = start_offset;
     Box::pin(stream! {
         loop {
             let page = req(page_size, offset).await?;
             offset += page.items.len() as u32;
-            for item in page.items {
+            let last = page.next.is_none();
+            yield Ok(page);
+            if last {
+                break;
+            }
+        }
+    })
+}
+
+/// Same as [`paginate_with_ctx`], but walking the pages backwards, starting at
+/// `start_offset` and ending with the first page. Both the pages and their
+/// items are yielded in reverse order, which is useful for tail-first views,
+/// e.g., the most recently added tracks of a playlist first.
+pub fn paginate_with_ctx_rev<'a, Ctx: 'a, T, Request>(
+    ctx: Ctx,
+    req: Request,
+    page_size: u32,
+    start_offset: u32,
+) -> Paginator<'a, ClientResult<T>>
+where
+    T: 'a + Unpin,
+    Request: 'a + for<'ctx> Fn(&'ctx Ctx, u32, u32) -> RequestFuture<'ctx, T>,
+{
+    use async_stream::stream;
+    let mut limit = page_size;
+    let mut offset = start_offset;
+    Box::pin(stream! {
+        loop {
+            let page = req(&ctx, limit, offset).await?;
+            for item in page.items.into_iter().rev() {
                 yield Ok(item);
             }
-            if page.next.is_none() {
+            if offset == 0 || page.previous.is_none() {
                 break;
             }
+            let previous = previous_page(page_size, offset);
+            limit = previous.0;
+            offset = previous.1;
+        }
+    })
+}
+
+/// Same as [`paginate`], but walking the pages backwards, starting at
+/// `start_offset` and ending with the first page. Both the pages and their
+/// items are yielded in reverse order, which is useful for tail-first views,
+/// e.g., the most recently added tracks of a playlist first.
+pub fn paginate_rev<'a, T, Fut, Request>(
+    req: Request,
+    page_size: u32,
+    start_offset: u32,
+) -> Paginator<'a, ClientResult<T>>
+where
+    T: 'a + Unpin,
+    Fut: Future<Output = ClientResult<Page<T>>>,
+    Request: 'a + Fn(u32, u32) -> Fut,
+{
+    use async_stream::stream;
+    let mut limit = page_size;
+    let mut offset = start_offset;
+    Box::pin(stream! {
+        loop {
+            let page = req(limit, offset).await?;
+            for item in page.items.into_iter().rev() {
+                yield Ok(item);
+            }
+            if offset == 0 || page.previous.is_none() {
+                break;
+            }
+            let previous = previous_page(page_size, offset);
+            limit = previous.0;
+            offset = previous.1;
+        }
+    })
+}
+
+/// Returns the limit and offset of the page before the one at `offset`. The
+/// first page is clamped so that it never requests a negative offset, nor
+/// items that have already been returned.
+fn previous_page(page_size: u32, offset: u32) -> (u32, u32) {
+    match offset.checked_sub(page_size) {
+        Some(previous) => (page_size, previous),
+        None => (offset, 0),
+    }
+}
+
+/// Flattens a stream of pages into a stream of their items.
+fn flatten_pages<'a, T>(
+    pages: Paginator<'a, ClientResult<Page<T>>>,
+) -> Paginator<'a, ClientResult<T>>
+where
+    T: 'a + Unpin,
+{
+    use async_stream::stream;
+    Box::pin(stream! {
+        for await page in pages {
+            for item in page?.items {
+                yield Ok(item);
+            }
         }
     })
 }
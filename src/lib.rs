@@ -47,12 +47,39 @@
 //! }
 //! ```
 //!
+//! In that case every endpoint is a regular blocking function, so no async
+//! runtime is needed at all, and the automatically paginated endpoints return
+//! an [`Iterator`] instead of a `Stream`:
+//!
+//! ```ignore
+//! let track = spotify.track(track_id, None)?;
+//! for album in spotify.current_user_saved_albums(None) {
+//!     println!("{}", album?.album.name);
+//! }
+//! ```
+//!
+//! ### Async runtimes
+//!
+//! RSpotify itself doesn't depend on a specific async runtime: the token is
+//! guarded by the mutex from [`futures`], and the retries and polling wait
+//! without relying on the timer of any runtime. However, reqwest requires a
+//! [Tokio][tokio-docs] runtime to perform the requests. With another runtime
+//! like `async-std`, RSpotify's futures must run inside a Tokio context, e.g.
+//! through a compatibility layer like [async-compat][async-compat-docs].
+//! Otherwise, the blocking `client-ureq` works without any runtime.
+//!
 //! ### Proxies
 //!
 //! [reqwest supports system proxies by default][reqwest-proxies]. It reads the
 //! environment variables `HTTP_PROXY` and `HTTPS_PROXY` environmental variables
 //! to set HTTP and HTTPS proxies, respectively.
 //!
+//! ureq uses the proxy in the first of the `HTTPS_PROXY`, `https_proxy`,
+//! `ALL_PROXY` and `all_proxy` environmental variables that is set, since all
+//! requests to Spotify are made through HTTPS. It can be overridden with
+//! `Config::proxy`; if that one is invalid, a warning is logged and the
+//! environment variables are used instead.
+//!
 //! ### Environmental variables
 //!
 //! RSpotify supports the [`dotenv`] crate, which allows you to save credentials
@@ -64,6 +91,17 @@
 //! rspotify = { version = "...", features = ["env-file"] }
 //! ```
 //!
+//! ### Encrypted token cache
+//!
+//! The cached token may be encrypted with a user-supplied key by using
+//! `EncryptedFileTokenCache` as the [`Config::token_cache`](Config#structfield.token_cache), available after
+//! enabling the `cache-encrypted` feature.
+//!
+//! ```toml
+//! [dependencies]
+//! rspotify = { version = "...", features = ["cache-encrypted"] }
+//! ```
+//!
 //! ### CLI utilities
 //!
 //! RSpotify includes basic support for Cli apps to obtain access tokens by
@@ -111,6 +149,8 @@
 //! [reqwest-docs]: https://docs.rs/reqwest/
 //! [reqwest-proxies]: https://docs.rs/reqwest/#proxies
 //! [ureq-docs]: https://docs.rs/ureq/
+//! [tokio-docs]: https://docs.rs/tokio/
+//! [async-compat-docs]: https://docs.rs/async-compat/
 //! [examples-github]: https://github.com/ramsayleung/rspotify/tree/master/examples
 //! [spotify-main]: https://developer.spotify.com/documentation/web-api/
 //! [spotify-auth-flows]: https://developer.spotify.com/documentation/general/guides/authorization/
@@ -123,6 +163,7 @@
 
 mod auth_code;
 mod auth_code_pkce;
+mod cache;
 mod client_creds;
 pub mod clients;
 pub mod sync;
@@ -135,23 +176,31 @@ pub use rspotify_model as model;
 // Top-level re-exports
 pub use auth_code::AuthCodeSpotify;
 pub use auth_code_pkce::AuthCodePkceSpotify;
+#[cfg(feature = "cache-encrypted")]
+pub use cache::EncryptedFileTokenCache;
+pub use cache::{FileTokenCache, TokenCache};
 pub use client_creds::ClientCredsSpotify;
 pub use macros::scopes;
 pub use model::Token;
 
-use crate::{http::HttpError, model::Id};
+use crate::{
+    http::{Headers, HttpClient, HttpError, RequestHook},
+    model::Id,
+};
 
 use std::{
     collections::{HashMap, HashSet},
     env,
     path::PathBuf,
+    sync::Arc,
+    time::Duration,
 };
 
 use getrandom::getrandom;
 use thiserror::Error;
 
 pub mod prelude {
-    pub use crate::clients::{BaseClient, OAuthClient};
+    pub use crate::clients::{pagination::PaginatorExt, BaseClient, OAuthClient};
     pub use crate::model::idtypes::{Id, PlayContextId, PlayableId};
 }
 
@@ -184,9 +233,10 @@ pub(crate) mod alphabets {
         b"abcdefghijklmnopqrstuvwxyzABCDEFGHIJKLMNOPQRSTUVWXYZ0123456789-._~";
 }
 
+/// Routes of the accounts service, relative to [`Config::auth_prefix`].
 pub(crate) mod auth_urls {
-    pub const AUTHORIZE: &str = "https://accounts.spotify.com/authorize";
-    pub const TOKEN: &str = "https://accounts.spotify.com/api/token";
+    pub const AUTHORIZE: &str = "authorize";
+    pub const TOKEN: &str = "api/token";
 }
 
 /// Possible errors returned from the `rspotify` client.
@@ -204,6 +254,15 @@ pub enum ClientError {
     #[error("http error: {0}")]
     Http(Box<HttpError>),
 
+    /// The request took longer than [`Config::timeout`].
+    #[error("request timed out")]
+    Timeout,
+
+    /// Spotify returned an unsuccessful status code. The error object it
+    /// responded with, if any, is parsed to provide more information.
+    #[error("api error: {0}")]
+    Api(model::ApiError),
+
     #[error("input/output error: {0}")]
     Io(#[from] std::io::Error),
 
@@ -216,58 +275,245 @@ pub enum ClientError {
 
     #[error("model error: {0}")]
     Model(#[from] model::ModelError),
+
+    #[error("invalid id: {0}")]
+    InvalidId(#[from] model::IdError),
+
+    /// The parameters given to an endpoint were rejected before performing
+    /// the request.
+    #[error("invalid input: {0}")]
+    InvalidInput(String),
+
+    /// The `state` in the authorization callback doesn't match the one the
+    /// authorization URL was generated with, so it may have been tampered
+    /// with.
+    #[error("the callback state doesn't match the request state")]
+    InvalidState,
+
+    /// More IDs were given than the endpoint accepts in a single request.
+    #[error("too many ids: at most {max} are accepted, got {got}")]
+    TooManyIds { max: usize, got: usize },
 }
 
-// The conversion has to be done manually because it's in a `Box<T>`
+// The conversion has to be done manually because it's in a `Box<T>`, and
+// timeouts have their own variant.
 impl From<HttpError> for ClientError {
     fn from(err: HttpError) -> Self {
-        Self::Http(Box::new(err))
+        if err.is_timeout() {
+            Self::Timeout
+        } else {
+            Self::Http(Box::new(err))
+        }
     }
 }
 
 pub type ClientResult<T> = Result<T, ClientError>;
 
 pub const DEFAULT_API_PREFIX: &str = "https://api.spotify.com/v1/";
+pub const DEFAULT_AUTH_PREFIX: &str = "https://accounts.spotify.com/";
 pub const DEFAULT_CACHE_PATH: &str = ".spotify_token_cache.json";
 pub const DEFAULT_PAGINATION_CHUNKS: u32 = 50;
+pub const DEFAULT_RETRY_BASE_DELAY: Duration = Duration::from_secs(1);
 
 /// Struct to configure the Spotify client.
+///
+/// Note that the options related to HTTP requests, like `max_retries`, are
+/// only taken into account when the client is built, i.e., when passed to
+/// `with_config`.
 #[derive(Debug, Clone)]
 pub struct Config {
     /// The Spotify API prefix, [`DEFAULT_API_PREFIX`] by default.
     pub prefix: String,
 
+    /// The prefix of the accounts service, used to authorize users and to
+    /// request and refresh tokens. [`DEFAULT_AUTH_PREFIX`] by default.
+    pub auth_prefix: String,
+
     /// The cache file path, in case it's used. By default it's
     /// [`DEFAULT_CACHE_PATH`]
     pub cache_path: PathBuf,
 
+    /// A custom storage for the token cache, in case it's used. By default
+    /// it's `None`, meaning that a [`FileTokenCache`] at `cache_path` is used.
+    pub token_cache: Option<Arc<dyn TokenCache>>,
+
     /// The pagination chunk size used when performing automatically paginated
     /// requests, like [`artist_albums`](crate::clients::BaseClient). This
     /// means that a request will be performed every `pagination_chunks` items.
     /// By default this is [`DEFAULT_PAGINATION_CHUNKS`].
     ///
     /// Note that most endpoints set a maximum to the number of items per
-    /// request, which most times is 50.
+    /// request, which most times is 50. Larger values are clamped to each
+    /// endpoint's maximum, so setting this to 100 only makes a difference for
+    /// the endpoints that accept it, like
+    /// [`playlist_items`](crate::clients::BaseClient::playlist_items).
     pub pagination_chunks: u32,
 
-    /// Whether or not to save the authentication token into a JSON file,
-    /// then reread the token from JSON file when launching the program without
-    /// following the full auth process again
+    /// Whether or not to save the authentication token into the cache (a JSON
+    /// file unless `token_cache` is set), then reread the token from it when
+    /// launching the program without following the full auth process again
     pub token_cached: bool,
 
     /// Whether or not to check if the token has expired when sending a
     /// request with credentials, and in that case, automatically refresh it.
     pub token_refreshing: bool,
+
+    /// Whether or not to refresh the token and replay the request once when
+    /// Spotify rejects it with `401 Unauthorized`, which may happen when its
+    /// clock is slightly off from ours. This only applies when
+    /// `token_refreshing` is enabled, and it's `true` by default.
+    pub refresh_on_unauthorized: bool,
+
+    /// How long before its actual expiration a token is considered expired,
+    /// so that it's refreshed before requests start failing. By default this
+    /// is 10 seconds.
+    pub token_expiry_skew: chrono::Duration,
+
+    /// The maximum number of times a request is retried after being rate
+    /// limited by Spotify (`429 Too Many Requests`). It's `0` by default, in
+    /// which case the rate limit error is returned directly.
+    pub max_retries: u32,
+
+    /// The time to wait before retrying a rate limited request when Spotify
+    /// doesn't include the `Retry-After` header. It's doubled after every
+    /// attempt, up to a minute, which also caps the delay in `Retry-After`.
+    /// By default this is [`DEFAULT_RETRY_BASE_DELAY`].
+    pub retry_base_delay: Duration,
+
+    /// The maximum number of times a request is retried after a transient
+    /// server error (`500`, `502`, `503` or `504`), waiting for
+    /// `retry_base_delay` doubled after every attempt, up to a minute. It's `0`
+    /// by default.
+    pub max_retries_5xx: u32,
+
+    /// Whether to randomize the delays before retrying after server errors,
+    /// so that many clients failing at once don't retry at once too. It's
+    /// `true` by default.
+    pub retry_jitter: bool,
+
+    /// Whether the requests other than `GET` are also retried after server
+    /// errors. Since they might have been applied in spite of the error,
+    /// e.g. adding the same items to a playlist twice, it's `false` by
+    /// default.
+    pub retry_5xx_non_idempotent: bool,
+
+    /// A pre-built HTTP client to perform the requests with, which may be
+    /// shared between multiple Spotify clients, or configured with custom
+    /// TLS settings, proxies and similars. With `client-reqwest`, it can be
+    /// created from a `reqwest::Client` with `HttpClient::from`. By default
+    /// it's `None`, meaning that a new one is created.
+    ///
+    /// Note that the rest of the HTTP options in this struct still apply to
+    /// it.
+    pub http_client: Option<HttpClient>,
+
+    /// The maximum time a request may take, after which it fails with
+    /// [`ClientError::Timeout`]. It's `None` by default, meaning that there's
+    /// no timeout.
+    pub timeout: Option<Duration>,
+
+    /// The proxy to send the requests through with `client-ureq`, such as
+    /// `http://localhost:8080`. It overrides the proxy in the environment
+    /// variables. It's `None` by default, meaning that the environment
+    /// variables are used (see the [proxies section](crate#proxies)), which
+    /// is also the case when it's not a valid proxy.
+    #[cfg(feature = "client-ureq")]
+    pub proxy: Option<String>,
+
+    /// A callback invoked after every HTTP request, with its method, URL,
+    /// status code and latency, e.g. to collect metrics. It's `None` by
+    /// default.
+    ///
+    /// ```
+    /// use rspotify::{http::RequestHook, Config};
+    ///
+    /// let config = Config {
+    ///     request_hook: Some(RequestHook::new(|info| {
+    ///         println!("{} {} -> {:?} in {:?}", info.method, info.url, info.status, info.elapsed);
+    ///     })),
+    ///     ..Default::default()
+    /// };
+    /// ```
+    pub request_hook: Option<RequestHook>,
+
+    /// The `User-Agent` header sent in every request, so that the application
+    /// can be identified. It's `None` by default, meaning that the HTTP
+    /// client's default is used.
+    pub user_agent: Option<String>,
+
+    /// Additional headers sent in every request, e.g. the ones required by a
+    /// corporate gateway. They never replace the headers specific to each
+    /// request, like the authorization. It's empty by default.
+    pub headers: Headers,
+
+    /// Whether to log the credentials sent in the requests, like the
+    /// `Authorization` header. They're replaced with `***` by default, since
+    /// logs may end up somewhere shared; only enable this for local
+    /// debugging.
+    pub log_secrets: bool,
 }
 
 impl Default for Config {
     fn default() -> Self {
         Self {
             prefix: String::from(DEFAULT_API_PREFIX),
+            auth_prefix: String::from(DEFAULT_AUTH_PREFIX),
             cache_path: PathBuf::from(DEFAULT_CACHE_PATH),
+            token_cache: None,
             pagination_chunks: DEFAULT_PAGINATION_CHUNKS,
             token_cached: false,
             token_refreshing: false,
+            refresh_on_unauthorized: true,
+            token_expiry_skew: chrono::Duration::seconds(10),
+            max_retries: 0,
+            retry_base_delay: DEFAULT_RETRY_BASE_DELAY,
+            max_retries_5xx: 0,
+            retry_jitter: true,
+            retry_5xx_non_idempotent: false,
+            http_client: None,
+            timeout: None,
+            #[cfg(feature = "client-ureq")]
+            proxy: None,
+            request_hook: None,
+            user_agent: None,
+            headers: Headers::new(),
+            log_secrets: false,
+        }
+    }
+}
+
+impl Config {
+    /// Builds the HTTP client with the options configured here.
+    pub(crate) fn build_http(&self) -> HttpClient {
+        let mut headers = self.headers.clone();
+        if let Some(user_agent) = &self.user_agent {
+            headers.insert("user-agent".to_owned(), user_agent.clone());
+        }
+
+        let http = self
+            .http_client
+            .clone()
+            .unwrap_or_default()
+            .with_retries(self.max_retries, self.retry_base_delay)
+            .with_server_error_retries(self.max_retries_5xx, self.retry_5xx_non_idempotent)
+            .with_retry_jitter(self.retry_jitter)
+            .with_timeout(self.timeout)
+            .with_request_hook(self.request_hook.clone())
+            .with_default_headers(&headers)
+            .with_log_secrets(self.log_secrets);
+
+        #[cfg(feature = "client-ureq")]
+        let http = http.with_proxy(self.proxy.as_deref());
+
+        http
+    }
+
+    /// Returns the configured token cache, or a [`FileTokenCache`] at
+    /// `cache_path` if there's none.
+    pub(crate) fn token_cache(&self) -> Arc<dyn TokenCache> {
+        match &self.token_cache {
+            Some(cache) => Arc::clone(cache),
+            None => Arc::new(FileTokenCache::new(&self.cache_path)),
         }
     }
 }
@@ -292,13 +538,44 @@ pub(crate) fn join_ids<'a, T: Id + 'a>(ids: impl IntoIterator<Item = T>) -> Stri
     ids.iter().map(Id::id).collect::<Vec<_>>().join(",")
 }
 
+/// Same as [`join_ids`], but fails with [`ClientError::TooManyIds`] if there
+/// are more than `max` IDs, so that the request isn't sent at all.
+#[inline]
+pub(crate) fn join_ids_limited<'a, T: Id + 'a>(
+    ids: impl IntoIterator<Item = T>,
+    max: usize,
+) -> ClientResult<String> {
+    let ids = ids.into_iter().collect::<Vec<_>>();
+    if ids.len() > max {
+        return Err(ClientError::TooManyIds {
+            max,
+            got: ids.len(),
+        });
+    }
+    Ok(join_ids(ids))
+}
+
+/// The scopes are sorted so that the resulting string is deterministic.
 #[inline]
 pub(crate) fn join_scopes(scopes: &HashSet<String>) -> String {
-    scopes
-        .iter()
-        .map(String::as_str)
-        .collect::<Vec<_>>()
-        .join(" ")
+    let mut scopes = scopes.iter().map(String::as_str).collect::<Vec<_>>();
+    scopes.sort_unstable();
+    scopes.join(" ")
+}
+
+/// Makes sure that none of the extra parameters of an authorization URL
+/// overrides one of the `reserved` ones, which are set by RSpotify.
+pub(crate) fn check_extra_params(
+    extra_params: &[(&str, &str)],
+    reserved: &[&str],
+) -> ClientResult<()> {
+    match extra_params.iter().find(|(key, _)| reserved.contains(key)) {
+        Some((key, _)) => Err(ClientError::InvalidInput(format!(
+            "`{}` is already set by RSpotify and can't be an extra parameter",
+            key
+        ))),
+        None => Ok(()),
+    }
 }
 
 /// Simple client credentials object for Spotify.
@@ -366,6 +643,10 @@ pub struct OAuth {
     pub redirect_uri: String,
     /// The state is generated by default, as suggested by the OAuth2 spec:
     /// [Cross-Site Request Forgery](https://tools.ietf.org/html/rfc6749#section-10.12)
+    ///
+    /// It's included in the authorization URL and checked in the callback. In
+    /// server scenarios where the client doesn't outlive the redirect, a
+    /// previously persisted state may be set here instead.
     pub state: String,
     /// You could use macro [scopes!](crate::scopes) to build it at compile time easily
     pub scopes: HashSet<String>,
@@ -400,13 +681,47 @@ impl OAuth {
             ..Default::default()
         })
     }
+
+    /// Returns the scopes in alphabetical order, since they're stored in a
+    /// `HashSet` with no particular order. This is the same order in which
+    /// they're sent to Spotify.
+    #[must_use]
+    pub fn scopes_sorted(&self) -> Vec<String> {
+        let mut scopes = self.scopes.iter().cloned().collect::<Vec<_>>();
+        scopes.sort_unstable();
+        scopes
+    }
 }
 
 #[cfg(test)]
 mod test {
-    use crate::{alphabets, generate_random_string, Credentials};
+    use crate::{alphabets, generate_random_string, join_scopes, scopes, Credentials, OAuth};
     use std::collections::HashSet;
 
+    #[test]
+    fn test_scopes_sorted() {
+        let oauth = OAuth {
+            scopes: scopes!(
+                "user-read-private",
+                "playlist-read-private",
+                "app-remote-control"
+            ),
+            ..Default::default()
+        };
+        assert_eq!(
+            oauth.scopes_sorted(),
+            [
+                "app-remote-control",
+                "playlist-read-private",
+                "user-read-private"
+            ]
+        );
+        assert_eq!(
+            join_scopes(&oauth.scopes),
+            "app-remote-control playlist-read-private user-read-private"
+        );
+    }
+
     #[test]
     fn test_generate_random_string() {
         let mut containers = HashSet::new();
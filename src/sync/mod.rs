@@ -12,5 +12,6 @@ use self::blocking as imp;
 use self::futures as imp;
 
 /// A type alias for either an asynchronous mutex or [`std::sync::Mutex`], depending on whether
-/// this library is compiled in asynchronous or synchronous mode.
+/// this library is compiled in asynchronous or synchronous mode. The asynchronous one wraps
+/// `futures::lock::Mutex`, which doesn't depend on any specific runtime.
 pub type Mutex<T> = imp::Mutex<T>;
@@ -22,6 +22,8 @@ fn test_disallow_key() {
 fn test_time_range() {
     let medium_range = TimeRange::MediumTerm;
     assert_eq!(<&str>::from(medium_range), "medium_term");
+    assert_eq!(<&str>::from(TimeRange::ShortTerm), "short_term");
+    assert_eq!(<&str>::from(TimeRange::LongTerm), "long_term");
 }
 
 #[test]
@@ -71,3 +73,11 @@ fn test_convert_country_from_str() {
     let zimbabwe = Country::Zimbabwe;
     assert_eq!(<&str>::from(zimbabwe), "ZW");
 }
+
+#[test]
+fn test_market() {
+    let from_token = Market::FromToken;
+    assert_eq!(<&str>::from(from_token), "from_token");
+    let zimbabwe = Market::Country(Country::Zimbabwe);
+    assert_eq!(<&str>::from(zimbabwe), "ZW");
+}
@@ -1,12 +1,12 @@
-use chrono::{DateTime, NaiveDateTime, Utc};
+use chrono::{DateTime, NaiveDate, NaiveDateTime, Utc};
 use rspotify::model::*;
-use serde::de::DeserializeOwned;
+use serde::{de::DeserializeOwned, Serialize};
 use std::time::Duration;
 
 #[track_caller]
 fn deserialize<T>(data: impl AsRef<str>) -> T
 where
-    T: DeserializeOwned + PartialEq + std::fmt::Debug,
+    T: DeserializeOwned + Serialize + PartialEq + std::fmt::Debug,
 {
     println!("from_str");
     let value = serde_json::from_str(data.as_ref()).unwrap();
@@ -15,6 +15,12 @@ where
     println!("from_reader");
     let _: T = serde_json::from_reader(data.as_ref().as_bytes()).unwrap();
 
+    // The models may be cached and read again, so serializing them must not
+    // lose any information
+    println!("round trip");
+    let serialized = serde_json::to_string(&value).unwrap();
+    assert_eq!(serde_json::from_str::<T>(&serialized).unwrap(), value);
+
     value
 }
 
@@ -151,6 +157,7 @@ fn test_full_artist() {
     let full_artist: FullArtist = deserialize(json_str);
     assert_eq!(full_artist.name, "Band of Horses");
     assert_eq!(full_artist.followers.total, 833247);
+    assert_eq!(full_artist.artist_id().id(), "0OdUWJ0sBjDrqHygGUXeCF");
 }
 
 #[test]
@@ -267,6 +274,177 @@ fn test_full_episode() {
     assert_eq!(full_episode.duration, duration);
 }
 
+#[test]
+fn test_saved_episode() {
+    let json_str = r#"
+    {
+        "added_at": "2022-05-01T10:20:30Z",
+        "episode": {
+            "audio_preview_url": null,
+            "description": "En ny tysk ",
+            "duration_ms": 1502795,
+            "explicit": false,
+            "external_urls": {},
+            "href": "https://api.spotify.com/v1/episodes/512ojhOuo1ktJprKbVcKyQ",
+            "id": "512ojhOuo1ktJprKbVcKyQ",
+            "images": [],
+            "is_externally_hosted": false,
+            "is_playable": true,
+            "language": "sv",
+            "languages": ["sv"],
+            "name": "Tredje rikets knarkande granskas",
+            "release_date": "2015-10-01",
+            "release_date_precision": "day",
+            "show": {
+                "available_markets": [],
+                "copyrights": [],
+                "description": "Vi är där historien är. Ansvarig utgivare: Nina Glans",
+                "explicit": false,
+                "external_urls": {},
+                "href": "https://api.spotify.com/v1/shows/38bS44xjbVVZ3No3ByF1dJ",
+                "id": "38bS44xjbVVZ3No3ByF1dJ",
+                "images": [],
+                "is_externally_hosted": false,
+                "languages": ["sv"],
+                "media_type": "audio",
+                "name": "Vetenskapsradion Historia",
+                "publisher": "Sveriges Radio",
+                "type": "show",
+                "uri": "spotify:show:38bS44xjbVVZ3No3ByF1dJ"
+            },
+            "type": "episode",
+            "uri": "spotify:episode:512ojhOuo1ktJprKbVcKyQ"
+        }
+    }
+    "#;
+    let saved_episode: SavedEpisode = deserialize(json_str);
+    let added_at = DateTime::parse_from_rfc3339("2022-05-01T10:20:30Z")
+        .unwrap()
+        .with_timezone(&Utc);
+    assert_eq!(saved_episode.added_at, added_at);
+    assert_eq!(saved_episode.episode.id.id(), "512ojhOuo1ktJprKbVcKyQ");
+}
+
+#[test]
+fn test_saved_show() {
+    let json_str = r#"
+    {
+        "added_at": "2021-03-11T08:00:00Z",
+        "show": {
+            "available_markets": [],
+            "copyrights": [],
+            "description": "Vi är där historien är. Ansvarig utgivare: Nina Glans",
+            "explicit": false,
+            "external_urls": {},
+            "href": "https://api.spotify.com/v1/shows/38bS44xjbVVZ3No3ByF1dJ",
+            "id": "38bS44xjbVVZ3No3ByF1dJ",
+            "images": [],
+            "is_externally_hosted": false,
+            "languages": ["sv"],
+            "media_type": "audio",
+            "name": "Vetenskapsradion Historia",
+            "publisher": "Sveriges Radio",
+            "type": "show",
+            "uri": "spotify:show:38bS44xjbVVZ3No3ByF1dJ"
+        }
+    }
+    "#;
+    let saved_show: Show = deserialize(json_str);
+    let added_at = DateTime::parse_from_rfc3339("2021-03-11T08:00:00Z")
+        .unwrap()
+        .with_timezone(&Utc);
+    assert_eq!(saved_show.added_at, added_at);
+}
+
+#[test]
+fn test_simplified_audiobook() {
+    let json_str = r#"
+        {
+            "authors": [
+                {
+                    "name": "Frank Herbert"
+                }
+            ],
+            "available_markets": ["US", "GB"],
+            "copyrights": [
+                {
+                    "text": "Copyright Frank Herbert",
+                    "type": "C"
+                }
+            ],
+            "description": "Set on the desert planet Arrakis.",
+            "html_description": "<p>Set on the desert planet Arrakis.</p>",
+            "edition": "Unabridged",
+            "explicit": false,
+            "external_urls": {
+                "spotify": "https://open.spotify.com/show/7iHfbu1YPACw6oZPAFJtqe"
+            },
+            "href": "https://api.spotify.com/v1/audiobooks/7iHfbu1YPACw6oZPAFJtqe",
+            "id": "7iHfbu1YPACw6oZPAFJtqe",
+            "images": [
+                {
+                    "height": 640,
+                    "url": "https://i.scdn.co/image/ab676663000022a8e3c8e5b7a4e8c1b9b8c6d2a1",
+                    "width": 640
+                }
+            ],
+            "languages": ["English"],
+            "media_type": "audio",
+            "name": "Dune",
+            "narrators": [
+                {
+                    "name": "Scott Brick"
+                }
+            ],
+            "publisher": "Frank Herbert",
+            "type": "audiobook",
+            "uri": "spotify:show:7iHfbu1YPACw6oZPAFJtqe",
+            "total_chapters": 52
+        }
+        "#;
+    let audiobook: SimplifiedAudiobook = deserialize(json_str);
+    assert_eq!(audiobook.id.id(), "7iHfbu1YPACw6oZPAFJtqe");
+    assert_eq!(audiobook.authors[0].name, "Frank Herbert");
+    assert_eq!(audiobook.narrators[0].name, "Scott Brick");
+    assert_eq!(audiobook.total_chapters, 52);
+}
+
+#[test]
+fn test_simplified_chapter() {
+    let json_str = r#"
+        {
+            "audio_preview_url": "https://p.scdn.co/mp3-preview/2f37da1d4221f40b9d1a98cd191f4d6f1646ad17",
+            "available_markets": ["US"],
+            "chapter_number": 1,
+            "description": "We kept on ascending, with occasional periods of quick descent.",
+            "html_description": "<p>We kept on ascending, with occasional periods of quick descent.</p>",
+            "duration_ms": 1686230,
+            "explicit": false,
+            "external_urls": {
+                "spotify": "https://open.spotify.com/episode/0D5wENdkdwbqlrHoaJ9g29"
+            },
+            "href": "https://api.spotify.com/v1/chapters/0D5wENdkdwbqlrHoaJ9g29",
+            "id": "0D5wENdkdwbqlrHoaJ9g29",
+            "images": [],
+            "is_playable": true,
+            "languages": ["en"],
+            "name": "Chapter 1",
+            "release_date": "1981-12",
+            "release_date_precision": "month",
+            "resume_point": {
+                "fully_played": false,
+                "resume_position_ms": 0
+            },
+            "type": "episode",
+            "uri": "spotify:episode:0D5wENdkdwbqlrHoaJ9g29"
+        }
+        "#;
+    let chapter: SimplifiedChapter = deserialize(json_str);
+    assert_eq!(chapter.chapter_number, 1);
+    assert_eq!(chapter.release_date_precision, DatePrecision::Month);
+    assert_eq!(chapter.duration, Duration::from_millis(1686230));
+}
+
 #[test]
 fn test_copyright() {
     let json_str = r#"
@@ -599,6 +777,161 @@ fn test_full_track() {
     let full_track: FullTrack = deserialize(json);
     let duration = Duration::from_millis(207959);
     assert_eq!(full_track.duration, duration);
+    assert_eq!(
+        full_track.external_ids.isrc.as_deref(),
+        Some("USUM71703861")
+    );
+    assert_eq!(full_track.external_ids.upc, None);
+
+    let track_id: Option<TrackId> = (&full_track).into();
+    assert_eq!(track_id, full_track.track_id());
+    assert_eq!(track_id.unwrap().id(), "11dFghVXANMlKmJXsNCbNl");
+
+    assert!(full_track.is_available_in(Country::SouthAfrica));
+    assert!(!full_track.is_available_in(Country::UnitedStates));
+    let markets = full_track.available_markets_set();
+    assert!(markets.contains(&Country::SouthAfrica));
+    assert!(!markets.contains(&Country::UnitedStates));
+
+    // The codes that aren't known are skipped
+    let track = FullTrack {
+        available_markets: vec!["ZA".to_owned(), "XX".to_owned()],
+        ..full_track.clone()
+    };
+    let markets = track.available_markets_set();
+    assert_eq!(markets.len(), 1);
+    assert!(markets.contains(&Country::SouthAfrica));
+
+    let release_date = full_track.album.parsed_release_date().unwrap();
+    assert_eq!(release_date.precision(), DatePrecision::Day);
+    assert_eq!(release_date.as_date(), NaiveDate::from_ymd_opt(2017, 5, 26));
+}
+
+#[test]
+fn test_release_date() {
+    let date = ReleaseDate::parse("1981", DatePrecision::Year).unwrap();
+    assert_eq!(date.year(), 1981);
+    assert_eq!(date.month(), None);
+    assert_eq!(date.as_date(), NaiveDate::from_ymd_opt(1981, 1, 1));
+
+    let date = ReleaseDate::parse("1981-12", DatePrecision::Month).unwrap();
+    assert_eq!(date.month(), Some(12));
+    assert_eq!(date.day(), None);
+    assert_eq!(date.as_date(), NaiveDate::from_ymd_opt(1981, 12, 1));
+
+    let date = ReleaseDate::parse("2020-02-29", DatePrecision::Day).unwrap();
+    assert_eq!(date.day(), Some(29));
+    assert_eq!(date.as_date(), NaiveDate::from_ymd_opt(2020, 2, 29));
+
+    assert_eq!(ReleaseDate::parse("1981-13", DatePrecision::Month), None);
+    assert_eq!(ReleaseDate::parse("1981-00", DatePrecision::Month), None);
+    assert_eq!(ReleaseDate::parse("1981-12-32", DatePrecision::Day), None);
+    assert_eq!(ReleaseDate::parse("1981-12-00", DatePrecision::Day), None);
+    assert_eq!(ReleaseDate::parse("2021-02-30", DatePrecision::Day), None);
+    assert_eq!(ReleaseDate::parse("1981-12", DatePrecision::Day), None);
+    assert_eq!(
+        ReleaseDate::parse("1981-12-01-01", DatePrecision::Day),
+        None
+    );
+    assert_eq!(ReleaseDate::parse("unknown", DatePrecision::Year), None);
+
+    // The precision is parsed with the same names Spotify uses
+    assert_eq!("month".parse(), Ok(DatePrecision::Month));
+    assert!("week".parse::<DatePrecision>().is_err());
+}
+
+#[test]
+fn test_relinked_track() {
+    let json = r#"
+    {
+  "album": {
+    "album_type": "single",
+    "artists": [
+      {
+        "external_urls": {
+          "spotify": "https://open.spotify.com/artist/6sFIWsNpZYqfjUpaCgueju"
+        },
+        "href": "https://api.spotify.com/v1/artists/6sFIWsNpZYqfjUpaCgueju",
+        "id": "6sFIWsNpZYqfjUpaCgueju",
+        "name": "Carly Rae Jepsen",
+        "type": "artist",
+        "uri": "spotify:artist:6sFIWsNpZYqfjUpaCgueju"
+      }
+    ],
+    "available_markets": [
+      "ZA"
+    ],
+    "external_urls": {
+      "spotify": "https://open.spotify.com/album/0tGPJ0bkWOUmH7MEOR77qc"
+    },
+    "href": "https://api.spotify.com/v1/albums/0tGPJ0bkWOUmH7MEOR77qc",
+    "id": "0tGPJ0bkWOUmH7MEOR77qc",
+    "images": [
+      {
+        "height": 64,
+        "url": "https://i.scdn.co/image/5a73a056d0af707b4119a883d87285feda543fbb",
+        "width": 64
+      }
+    ],
+    "name": "Cut To The Feeling",
+    "release_date": "2017-05-26",
+    "release_date_precision": "day",
+    "type": "album",
+    "uri": "spotify:album:0tGPJ0bkWOUmH7MEOR77qc"
+  },
+  "artists": [
+    {
+      "external_urls": {
+        "spotify": "https://open.spotify.com/artist/6sFIWsNpZYqfjUpaCgueju"
+      },
+      "href": "https://api.spotify.com/v1/artists/6sFIWsNpZYqfjUpaCgueju",
+      "id": "6sFIWsNpZYqfjUpaCgueju",
+      "name": "Carly Rae Jepsen",
+      "type": "artist",
+      "uri": "spotify:artist:6sFIWsNpZYqfjUpaCgueju"
+    }
+  ],
+  "available_markets": [
+    "ZA"
+  ],
+  "disc_number": 1,
+  "duration_ms": 207959,
+  "explicit": false,
+  "external_ids": {
+    "isrc": "USUM71703861"
+  },
+  "external_urls": {
+    "spotify": "https://open.spotify.com/track/11dFghVXANMlKmJXsNCbNl"
+  },
+  "href": "https://api.spotify.com/v1/tracks/11dFghVXANMlKmJXsNCbNl",
+  "id": "11dFghVXANMlKmJXsNCbNl",
+  "is_local": false,
+  "is_playable": true,
+  "linked_from": {
+    "external_urls": {
+      "spotify": "https://open.spotify.com/track/2ibou0NbHtmzljbJ9D2RYF"
+    },
+    "href": "https://api.spotify.com/v1/tracks/2ibou0NbHtmzljbJ9D2RYF",
+    "id": "2ibou0NbHtmzljbJ9D2RYF",
+    "type": "track",
+    "uri": "spotify:track:2ibou0NbHtmzljbJ9D2RYF"
+  },
+  "name": "Cut To The Feeling",
+  "popularity": 63,
+  "preview_url": "https://p.scdn.co/mp3-preview/3eb16018c2a700240e9dfb8817b6f2d041f15eb1?cid=774b29d4f13844c495f206cafdad9c86",
+  "track_number": 1,
+  "type": "track",
+  "uri": "spotify:track:11dFghVXANMlKmJXsNCbNl"
+}
+    "#;
+    let full_track: FullTrack = deserialize(json);
+
+    let playable_id = full_track.playable_id().unwrap();
+    assert_eq!(playable_id.id(), "11dFghVXANMlKmJXsNCbNl");
+    assert_eq!(
+        full_track.original_id().unwrap().id(),
+        "2ibou0NbHtmzljbJ9D2RYF"
+    );
 }
 
 #[test]
@@ -1128,3 +1461,246 @@ fn test_simplified_playlist() {
     );
     assert_eq!(simplified_playlist.tracks.total, 62);
 }
+
+#[test]
+fn test_search_results() {
+    let json_str = r#"
+        {
+            "artists": {
+                "href": "https://api.spotify.com/v1/search?query=tania+bowra&type=artist&offset=0&limit=1",
+                "items": [],
+                "limit": 1,
+                "next": null,
+                "offset": 0,
+                "previous": null,
+                "total": 0
+            },
+            "tracks": {
+                "href": "https://api.spotify.com/v1/search?query=tania+bowra&type=track&offset=0&limit=1",
+                "items": [],
+                "limit": 1,
+                "next": null,
+                "offset": 0,
+                "previous": null,
+                "total": 0
+            }
+        }
+        "#;
+    let results: SearchResults = deserialize(json_str);
+    assert!(results.artists.is_some());
+    assert!(results.tracks.is_some());
+    assert!(results.albums.is_none());
+    assert!(results.playlists.is_none());
+    assert!(results.shows.is_none());
+    assert!(results.episodes.is_none());
+}
+
+#[test]
+fn test_search_result_shows() {
+    let json_str = r#"
+        {
+            "shows": {
+                "href": "https://api.spotify.com/v1/search?query=vetenskapsradion&type=show&offset=0&limit=1",
+                "items": [
+                    {
+                        "available_markets": ["SE"],
+                        "copyrights": [],
+                        "description": "Vi förklarar vetenskapens senaste rön.",
+                        "explicit": false,
+                        "external_urls": {
+                            "spotify": "https://open.spotify.com/show/38bS44xjbVVZ3No3ByF1dJ"
+                        },
+                        "href": "https://api.spotify.com/v1/shows/38bS44xjbVVZ3No3ByF1dJ",
+                        "id": "38bS44xjbVVZ3No3ByF1dJ",
+                        "images": [],
+                        "is_externally_hosted": false,
+                        "languages": ["sv"],
+                        "media_type": "audio",
+                        "name": "Vetenskapsradion Historia",
+                        "publisher": "Sveriges Radio",
+                        "type": "show",
+                        "uri": "spotify:show:38bS44xjbVVZ3No3ByF1dJ"
+                    }
+                ],
+                "limit": 1,
+                "next": null,
+                "offset": 0,
+                "previous": null,
+                "total": 1
+            }
+        }
+        "#;
+    let result: SearchResult = deserialize(json_str);
+    match result {
+        SearchResult::Shows(page) => {
+            assert_eq!(page.items[0].id.id(), "38bS44xjbVVZ3No3ByF1dJ")
+        }
+        _ => panic!("expected shows, got {result:?}"),
+    }
+}
+
+#[test]
+fn test_search_result_episodes() {
+    let json_str = r#"
+        {
+            "episodes": {
+                "href": "https://api.spotify.com/v1/search?query=bellman&type=episode&offset=0&limit=1",
+                "items": [
+                    {
+                        "audio_preview_url": "https://p.scdn.co/mp3-preview/d8b916e1872de2bb0285d8c7bfe2b4b57011c85c",
+                        "description": "Nu grävs Carl Michael Bellmans kvarter fram på Södermalm i Stockholm.",
+                        "duration_ms": 2685023,
+                        "explicit": false,
+                        "external_urls": {
+                            "spotify": "https://open.spotify.com/episode/3brfPv3PaUhspkm1T9ZVl8"
+                        },
+                        "href": "https://api.spotify.com/v1/episodes/3brfPv3PaUhspkm1T9ZVl8",
+                        "id": "3brfPv3PaUhspkm1T9ZVl8",
+                        "images": [],
+                        "is_externally_hosted": false,
+                        "is_playable": true,
+                        "language": "sv",
+                        "languages": ["sv"],
+                        "name": "På Bellmans bakgata",
+                        "release_date": "2020-10-20",
+                        "release_date_precision": "day",
+                        "type": "episode",
+                        "uri": "spotify:episode:3brfPv3PaUhspkm1T9ZVl8"
+                    }
+                ],
+                "limit": 1,
+                "next": null,
+                "offset": 0,
+                "previous": null,
+                "total": 1
+            }
+        }
+        "#;
+    let result: SearchResult = deserialize(json_str);
+    match result {
+        SearchResult::Episodes(page) => {
+            assert_eq!(page.items[0].id.id(), "3brfPv3PaUhspkm1T9ZVl8")
+        }
+        _ => panic!("expected episodes, got {result:?}"),
+    }
+}
+
+#[test]
+fn test_playlist_items_with_null_track() {
+    let json_str = r#"
+    {
+        "href": "https://api.spotify.com/v1/playlists/37i9dQZF1DX4UtSsGT1Sbe/tracks?offset=0&limit=100",
+        "items": [
+            {
+                "added_at": "2021-03-01T12:00:00Z",
+                "added_by": {
+                    "external_urls": {
+                        "spotify": "https://open.spotify.com/user/spotify"
+                    },
+                    "href": "https://api.spotify.com/v1/users/spotify",
+                    "id": "spotify",
+                    "type": "user",
+                    "uri": "spotify:user:spotify"
+                },
+                "is_local": false,
+                "track": null
+            }
+        ],
+        "limit": 100,
+        "next": null,
+        "offset": 0,
+        "previous": null,
+        "total": 1
+    }
+    "#;
+    let page: Page<PlaylistItem> = deserialize(json_str);
+    assert_eq!(page.items.len(), 1);
+    assert!(page.items[0].track.is_none());
+    assert_eq!(page.items[0].added_by.as_ref().unwrap().id.id(), "spotify");
+}
+
+#[test]
+fn test_unknown_enum_values() {
+    let json_str = r#"
+    {
+        "id": "28d0f845293d03a2713392905c6d30b6442719b5",
+        "is_active": true,
+        "is_private_session": false,
+        "is_restricted": false,
+        "name": "Kitchen",
+        "type": "Smartwatch",
+        "volume_percent": 100
+    }
+    "#;
+    let device: Device = deserialize(json_str);
+    assert_eq!(device._type, DeviceType::Unknown("Smartwatch".to_owned()));
+
+    // The unknown values are kept as is, so that they're serialized back
+    // unchanged
+    let album_type: AlbumType = deserialize(r#""audiobook""#);
+    assert_eq!(album_type, AlbumType::Unknown("audiobook".to_owned()));
+    assert_eq!(
+        serde_json::to_string(&album_type).unwrap(),
+        r#""audiobook""#
+    );
+    let copyright_type: CopyrightType = deserialize(r#""X""#);
+    assert_eq!(copyright_type, CopyrightType::Unknown("X".to_owned()));
+    assert_eq!(serde_json::to_string(&copyright_type).unwrap(), r#""X""#);
+    let tpe: Type = deserialize(r#""podcast""#);
+    assert_eq!(tpe, Type::Unknown("podcast".to_owned()));
+    assert_eq!(serde_json::to_string(&tpe).unwrap(), r#""podcast""#);
+    assert_eq!(tpe.to_string(), "podcast");
+    assert_eq!(
+        parse_uri("spotify:unknown:4iV5W9uYEdYUVa79Axb7Rh"),
+        Err(IdError::InvalidType)
+    );
+    let playing_type: CurrentlyPlayingType = deserialize(r#""jingle""#);
+    assert_eq!(
+        playing_type,
+        CurrentlyPlayingType::Unknown("jingle".to_owned())
+    );
+    assert_eq!(serde_json::to_string(&playing_type).unwrap(), r#""jingle""#);
+    let device_type = serde_json::to_value(&device._type).unwrap();
+    assert_eq!(device_type, "Smartwatch");
+
+    // The known values aren't mistaken for unknown ones
+    let album_type: AlbumType = deserialize(r#""appears_on""#);
+    assert_eq!(album_type, AlbumType::AppearsOn);
+    let device_type: DeviceType = deserialize(r#""TV""#);
+    assert_eq!(device_type, DeviceType::Tv);
+}
+
+#[test]
+fn test_api_error() {
+    let regular = r#"{"error": {"status": 404, "message": "Non existing id"}}"#;
+    let error = ApiError::from_response(404, regular);
+    assert_eq!(error.status, 404);
+    assert_eq!(error.message, "Non existing id");
+    assert_eq!(error.reason, None);
+
+    let player = r#"
+        {
+            "error": {
+                "status": 403,
+                "message": "Player command failed: Premium required",
+                "reason": "PREMIUM_REQUIRED"
+            }
+        }
+        "#;
+    let error = ApiError::from_response(403, player);
+    assert_eq!(error.status, 403);
+    assert_eq!(error.reason, Some(PlayerErrorReason::PremiumRequired));
+
+    let unknown_reason = r#"{"error": {"status": 403, "message": "", "reason": "SOMETHING_NEW"}}"#;
+    let error = ApiError::from_response(403, unknown_reason);
+    assert_eq!(error.reason, Some(PlayerErrorReason::Unknown));
+
+    let auth = r#"{"error": "invalid_client", "error_description": "Invalid client secret"}"#;
+    let error = ApiError::from_response(400, auth);
+    assert_eq!(error.status, 400);
+    assert_eq!(error.message, "invalid_client: Invalid client secret");
+
+    let error = ApiError::from_response(502, "Bad Gateway");
+    assert_eq!(error.status, 502);
+    assert_eq!(error.message, "Bad Gateway");
+}
@@ -1,11 +1,73 @@
 use chrono::prelude::*;
 use chrono::Duration;
 use rspotify::{
-    prelude::*, scopes, AuthCodeSpotify, ClientCredsSpotify, Config, Credentials, OAuth, Token,
+    model::{AlbumId, Country, Market, ResolvedItem, SearchType, TrackId},
+    prelude::*,
+    scopes, AuthCodePkceSpotify, AuthCodeSpotify, ClientCredsSpotify, ClientError, ClientResult,
+    Config, Credentials, OAuth, Token, TokenCache,
+};
+use std::{
+    collections::HashMap,
+    fs,
+    io::{BufRead, BufReader, Read, Write},
+    net::TcpListener,
+    path::PathBuf,
+    sync::{Arc, Mutex},
+    thread::{self, JoinHandle},
 };
-use std::{collections::HashMap, fs, io::Read, path::PathBuf};
 use url::Url;
 
+/// Starts a local HTTP server that answers each request it receives with the
+/// next of the given status codes and JSON bodies. It returns its URL, and a
+/// handle that yields the requests it received once all of them are answered.
+fn mock_server(responses: Vec<(u16, &'static str)>) -> (String, JoinHandle<Vec<String>>) {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let url = format!("http://{}/", listener.local_addr().unwrap());
+    let handle = thread::spawn(move || {
+        responses
+            .into_iter()
+            .map(|(status, body)| {
+                let (mut stream, _) = listener.accept().unwrap();
+
+                // The headers are read until the empty line, and then the
+                // body, if any
+                let mut reader = BufReader::new(&mut stream);
+                let mut request = String::new();
+                let mut content_length = 0;
+                loop {
+                    let mut line = String::new();
+                    reader.read_line(&mut line).unwrap();
+                    if let Some((key, val)) = line.split_once(':') {
+                        if key.eq_ignore_ascii_case("content-length") {
+                            content_length = val.trim().parse().unwrap();
+                        }
+                    }
+                    request.push_str(&line);
+                    if line == "\r\n" || line.is_empty() {
+                        break;
+                    }
+                }
+                let mut request_body = vec![0; content_length];
+                reader.read_exact(&mut request_body).unwrap();
+                request.push_str(&String::from_utf8(request_body).unwrap());
+
+                write!(
+                    stream,
+                    "HTTP/1.1 {} Mock\r\nContent-Type: application/json\r\n\
+                    Content-Length: {}\r\nConnection: close\r\n\r\n{}",
+                    status,
+                    body.len(),
+                    body
+                )
+                .unwrap();
+                request
+            })
+            .collect()
+    });
+
+    (url, handle)
+}
+
 #[test]
 fn test_get_authorize_url() {
     let oauth = OAuth {
@@ -32,6 +94,96 @@ fn test_get_authorize_url() {
     assert_eq!(hash_query.get("state").unwrap(), "fdsafdsfa");
 }
 
+#[test]
+fn test_get_authorize_url_with_state() {
+    let oauth = OAuth {
+        state: "fdsafdsfa".to_owned(),
+        redirect_uri: "localhost".to_owned(),
+        ..Default::default()
+    };
+    let creds = Credentials::new("this-is-my-client-id", "this-is-my-client-secret");
+
+    let spotify = AuthCodeSpotify::new(creds, oauth);
+
+    let authorize_url = spotify
+        .get_authorize_url_with_state("per-user-state", true)
+        .unwrap();
+    let hash_query: HashMap<_, _> = Url::parse(&authorize_url)
+        .unwrap()
+        .query_pairs()
+        .into_owned()
+        .collect();
+
+    assert_eq!(hash_query.get("state").unwrap(), "per-user-state");
+    assert_eq!(hash_query.get("show_dialog").unwrap(), "true");
+    assert_eq!(spotify.get_oauth().state, "fdsafdsfa");
+}
+
+#[test]
+fn test_get_authorize_url_with_params() {
+    let oauth = OAuth {
+        redirect_uri: "localhost".to_owned(),
+        ..Default::default()
+    };
+    let creds = Credentials::new("this-is-my-client-id", "this-is-my-client-secret");
+
+    let spotify = AuthCodeSpotify::new(creds, oauth);
+
+    let authorize_url = spotify
+        .get_authorize_url_with_params(false, &[("custom_param", "custom value")])
+        .unwrap();
+    let hash_query: HashMap<_, _> = Url::parse(&authorize_url)
+        .unwrap()
+        .query_pairs()
+        .into_owned()
+        .collect();
+
+    assert_eq!(hash_query.get("client_id").unwrap(), "this-is-my-client-id");
+    assert_eq!(hash_query.get("custom_param").unwrap(), "custom value");
+    assert!(!hash_query.contains_key("show_dialog"));
+}
+
+#[test]
+fn test_get_authorize_url_with_reserved_params() {
+    let oauth = OAuth {
+        redirect_uri: "localhost".to_owned(),
+        ..Default::default()
+    };
+    let creds = Credentials::new("this-is-my-client-id", "this-is-my-client-secret");
+
+    let spotify = AuthCodeSpotify::new(creds.clone(), oauth.clone());
+    for key in [
+        "state",
+        "client_id",
+        "redirect_uri",
+        "scope",
+        "response_type",
+        "show_dialog",
+    ] {
+        let result = spotify.get_authorize_url_with_params(false, &[(key, "overridden")]);
+        assert!(
+            matches!(result, Err(ClientError::InvalidInput(_))),
+            "{}",
+            key
+        );
+    }
+
+    let mut spotify = AuthCodePkceSpotify::new(creds, oauth);
+    for key in [
+        "state",
+        "code_challenge",
+        "code_challenge_method",
+        "response_type",
+    ] {
+        let result = spotify.get_authorize_url_with_params(None, &[(key, "overridden")]);
+        assert!(
+            matches!(result, Err(ClientError::InvalidInput(_))),
+            "{}",
+            key
+        );
+    }
+}
+
 #[maybe_async::test(feature = "__sync", async(feature = "__async", tokio::test))]
 async fn test_read_token_cache() {
     let expires_in = Duration::seconds(3600);
@@ -44,6 +196,7 @@ async fn test_read_token_cache() {
         access_token: "test-access_token".to_owned(),
         scopes: scopes.clone(),
         refresh_token: Some("...".to_owned()),
+        ..Default::default()
     };
 
     let config = Config {
@@ -83,6 +236,7 @@ async fn test_write_token() {
         expires_at: Some(now),
         scopes: scopes.clone(),
         refresh_token: Some("...".to_owned()),
+        ..Default::default()
     };
 
     let config = Config {
@@ -110,6 +264,386 @@ async fn test_write_token() {
     fs::remove_file(&spotify.config.cache_path).unwrap();
 }
 
+/// A token cache that keeps the token in memory, as an example of a custom
+/// implementation.
+#[derive(Debug, Default)]
+struct MemoryTokenCache(Mutex<Option<Token>>);
+
+#[maybe_async::maybe_async]
+impl TokenCache for MemoryTokenCache {
+    async fn load(&self) -> ClientResult<Option<Token>> {
+        Ok(self.0.lock().unwrap().clone())
+    }
+
+    async fn save(&self, token: &Token) -> ClientResult<()> {
+        *self.0.lock().unwrap() = Some(token.clone());
+        Ok(())
+    }
+}
+
+#[maybe_async::test(feature = "__sync", async(feature = "__async", tokio::test))]
+async fn test_custom_token_cache() {
+    let tok = Token {
+        access_token: "test-access_token".to_owned(),
+        expires_in: Duration::seconds(3600),
+        expires_at: Some(Utc::now() + Duration::seconds(3600)),
+        scopes: scopes!("playlist-read-private"),
+        refresh_token: Some("...".to_owned()),
+        ..Default::default()
+    };
+
+    let cache = Arc::new(MemoryTokenCache::default());
+    let config = Config {
+        token_cached: true,
+        cache_path: PathBuf::from(".test_custom_token_cache.json"),
+        token_cache: Some(cache.clone()),
+        ..Default::default()
+    };
+
+    // Nothing has been cached yet
+    let mut spotify = ClientCredsSpotify::default();
+    spotify.config = config.clone();
+    let cached = spotify.read_token_cache().await.unwrap();
+    assert!(cached.is_none());
+
+    let mut predefined_spotify = ClientCredsSpotify::from_token(tok.clone());
+    predefined_spotify.config = config;
+    predefined_spotify.write_token_cache().await.unwrap();
+
+    // The token is saved into the custom cache instead of the file
+    assert!(!predefined_spotify.config.cache_path.exists());
+    assert_eq!(cache.0.lock().unwrap().as_ref(), Some(&tok));
+    let tok_from_cache = spotify.read_token_cache().await.unwrap().unwrap();
+    assert_eq!(tok_from_cache, tok);
+}
+
+#[cfg(feature = "cache-encrypted")]
+#[maybe_async::test(feature = "__sync", async(feature = "__async", tokio::test))]
+async fn test_encrypted_token_cache() {
+    use rspotify::{ClientError, EncryptedFileTokenCache};
+
+    let tok = Token {
+        access_token: "test-access_token".to_owned(),
+        expires_in: Duration::seconds(3600),
+        expires_at: Some(Utc::now() + Duration::seconds(3600)),
+        scopes: scopes!("playlist-read-private"),
+        refresh_token: Some("...".to_owned()),
+        ..Default::default()
+    };
+
+    let path = PathBuf::from(".test_encrypted_token_cache.json");
+    let cache = EncryptedFileTokenCache::new(&path, [7; 32]);
+    cache.save(&tok).await.unwrap();
+
+    // The token isn't stored in plain text
+    let contents = fs::read(&path).unwrap();
+    assert!(!String::from_utf8_lossy(&contents).contains("test-access_token"));
+    let loaded = cache.load().await.unwrap();
+    assert_eq!(loaded, Some(tok));
+
+    // Using the wrong key fails instead of returning a corrupt token
+    let wrong_cache = EncryptedFileTokenCache::new(&path, [8; 32]);
+    let loaded = wrong_cache.load().await;
+    assert!(matches!(loaded, Err(ClientError::CacheFile(_))));
+
+    fs::remove_file(&path).unwrap();
+}
+
+#[maybe_async::test(feature = "__sync", async(feature = "__async", tokio::test))]
+async fn test_unauthorized_without_refresh_token() {
+    let (url, server) = mock_server(vec![(
+        401,
+        r#"{"error": {"status": 401, "message": "The access token expired"}}"#,
+    )]);
+    let tok = Token {
+        access_token: "test-access_token".to_owned(),
+        expires_at: Some(Utc::now() + Duration::seconds(3600)),
+        refresh_token: None,
+        ..Default::default()
+    };
+
+    let mut spotify = AuthCodeSpotify::from_token(tok.clone());
+    spotify.config = Config {
+        prefix: url,
+        token_refreshing: true,
+        ..Default::default()
+    };
+
+    // Without a refresh token, the rejection is returned instead of replaying
+    // the request, and the token is kept
+    let result = spotify.current_user().await;
+    assert!(matches!(result, Err(ClientError::Api(err)) if err.status == 401));
+    let current = spotify.token.lock().await.unwrap().clone();
+    assert_eq!(current, Some(tok));
+
+    let requests = server.join().unwrap();
+    assert_eq!(requests.len(), 1);
+    assert!(requests[0].starts_with("GET /me/ "));
+}
+
+#[maybe_async::test(feature = "__sync", async(feature = "__async", tokio::test))]
+async fn test_request_token_pkce() {
+    let (url, server) = mock_server(vec![(
+        200,
+        r#"{"access_token": "test-access_token", "token_type": "Bearer", "expires_in": 3600, "scope": ""}"#,
+    )]);
+    let creds = Credentials::new_pkce("this-is-my-client-id");
+    let oauth = OAuth {
+        redirect_uri: "http://localhost:8000".to_owned(),
+        ..Default::default()
+    };
+    let config = Config {
+        auth_prefix: url,
+        ..Default::default()
+    };
+
+    let mut spotify = AuthCodePkceSpotify::with_config(creds, oauth, config);
+    spotify.get_authorize_url(None).unwrap();
+    spotify.request_token("test-code").await.unwrap();
+    let token = spotify.token.lock().await.unwrap().clone();
+    assert_eq!(token.unwrap().access_token, "test-access_token");
+
+    // The code verifier generated for the authorization URL is sent when
+    // requesting the token
+    let requests = server.join().unwrap();
+    assert_eq!(requests.len(), 1);
+    assert!(requests[0].starts_with("POST /api/token "));
+    let (_, body) = requests[0].split_once("\r\n\r\n").unwrap();
+    let payload: HashMap<_, _> = url::form_urlencoded::parse(body.as_bytes()).collect();
+    assert_eq!(payload["code"], "test-code");
+    assert_eq!(payload["code_verifier"], spotify.verifier.unwrap().as_str());
+    assert_eq!(payload["client_id"], "this-is-my-client-id");
+}
+
+#[maybe_async::test(feature = "__sync", async(feature = "__async", tokio::test))]
+async fn test_clones_share_refreshed_token() {
+    let (url, server) = mock_server(vec![(
+        200,
+        r#"{"access_token": "new-access_token", "token_type": "Bearer", "expires_in": 3600, "scope": ""}"#,
+    )]);
+    let tok = Token {
+        access_token: "test-access_token".to_owned(),
+        expires_at: Some(Utc::now() + Duration::seconds(3600)),
+        refresh_token: Some("test-refresh_token".to_owned()),
+        ..Default::default()
+    };
+    let creds = Credentials::new("this-is-my-client-id", "this-is-my-client-secret");
+    let config = Config {
+        auth_prefix: url,
+        ..Default::default()
+    };
+
+    let spotify = AuthCodeSpotify::with_config(creds, OAuth::default(), config);
+    *spotify.token.lock().await.unwrap() = Some(tok);
+    let clone = spotify.clone();
+
+    // The token refreshed by one of the clones is observed by the other one
+    clone.refresh_token().await.unwrap();
+    let shared = spotify.token.lock().await.unwrap().clone().unwrap();
+    assert_eq!(shared.access_token, "new-access_token");
+    assert_eq!(shared.refresh_token.as_deref(), Some("test-refresh_token"));
+
+    let requests = server.join().unwrap();
+    assert_eq!(requests.len(), 1);
+    assert!(requests[0].starts_with("POST /api/token "));
+}
+
+#[maybe_async::test(feature = "__sync", async(feature = "__async", tokio::test))]
+async fn test_refresh_token_rotated() {
+    let response = r#"{"access_token": "new-access_token", "token_type": "Bearer", "expires_in": 3600, "scope": "", "refresh_token": "new-refresh_token"}"#;
+    let (url, server) = mock_server(vec![(200, response), (200, response)]);
+    let tok = Token {
+        access_token: "test-access_token".to_owned(),
+        expires_at: Some(Utc::now() + Duration::seconds(3600)),
+        refresh_token: Some("test-refresh_token".to_owned()),
+        ..Default::default()
+    };
+    let cache = Arc::new(MemoryTokenCache::default());
+    let config = Config {
+        auth_prefix: url,
+        token_cached: true,
+        token_cache: Some(cache.clone()),
+        ..Default::default()
+    };
+
+    // When Spotify rotates the refresh token, the new one replaces the
+    // previous one both in the client and in the cache
+    let creds = Credentials::new("this-is-my-client-id", "this-is-my-client-secret");
+    let spotify = AuthCodeSpotify::with_config(creds, OAuth::default(), config.clone());
+    *spotify.token.lock().await.unwrap() = Some(tok.clone());
+    spotify.refresh_token().await.unwrap();
+    let current = spotify.token.lock().await.unwrap().clone().unwrap();
+    assert_eq!(current.refresh_token.as_deref(), Some("new-refresh_token"));
+    assert!(!serde_json::to_string(&current)
+        .unwrap()
+        .contains("test-refresh_token"));
+    let cached = cache.0.lock().unwrap().take().unwrap();
+    assert_eq!(cached.refresh_token.as_deref(), Some("new-refresh_token"));
+
+    // Same with PKCE
+    let creds = Credentials::new_pkce("this-is-my-client-id");
+    let spotify = AuthCodePkceSpotify::with_config(creds, OAuth::default(), config);
+    *spotify.token.lock().await.unwrap() = Some(tok);
+    spotify.refresh_token().await.unwrap();
+    let current = spotify.token.lock().await.unwrap().clone().unwrap();
+    assert_eq!(current.refresh_token.as_deref(), Some("new-refresh_token"));
+    assert!(!serde_json::to_string(&current)
+        .unwrap()
+        .contains("test-refresh_token"));
+    let cached = cache.0.lock().unwrap().take().unwrap();
+    assert_eq!(cached.refresh_token.as_deref(), Some("new-refresh_token"));
+
+    // The previous refresh token is the one sent to obtain the new one
+    let requests = server.join().unwrap();
+    assert_eq!(requests.len(), 2);
+    for request in requests {
+        let (_, body) = request.split_once("\r\n\r\n").unwrap();
+        let payload: HashMap<_, _> = url::form_urlencoded::parse(body.as_bytes()).collect();
+        assert_eq!(payload["refresh_token"], "test-refresh_token");
+    }
+}
+
+#[maybe_async::test(feature = "__sync", async(feature = "__async", tokio::test))]
+async fn test_default_headers() {
+    let (url, server) = mock_server(vec![(
+        200,
+        r#"{"id": "test-user", "display_name": null, "external_urls": {}, "href": "https://api.spotify.com/v1/users/test-user", "images": []}"#,
+    )]);
+    let tok = Token {
+        access_token: "test-access_token".to_owned(),
+        expires_at: Some(Utc::now() + Duration::seconds(3600)),
+        ..Default::default()
+    };
+    let mut headers = HashMap::new();
+    headers.insert("X-Custom".to_owned(), "custom value".to_owned());
+    headers.insert("Authorization".to_owned(), "Bearer default".to_owned());
+    // Invalid headers are ignored instead of making every request fail
+    headers.insert("Invalid Header".to_owned(), "value".to_owned());
+    headers.insert("X-Invalid".to_owned(), "line\nbreak".to_owned());
+    let config = Config {
+        prefix: url,
+        headers,
+        ..Default::default()
+    };
+
+    let spotify = AuthCodeSpotify::with_config(Credentials::default(), OAuth::default(), config);
+    *spotify.token.lock().await.unwrap() = Some(tok);
+    spotify.current_user().await.unwrap();
+
+    // The authorization of each request isn't overridden by the default one
+    let requests = server.join().unwrap();
+    assert_eq!(requests.len(), 1);
+    let request = requests[0].to_lowercase();
+    assert!(request.contains("\r\nx-custom: custom value\r\n"));
+    assert!(request.contains("\r\nauthorization: bearer test-access_token\r\n"));
+    assert!(!request.contains("bearer default"));
+    assert!(!request.contains("invalid"));
+}
+
+#[maybe_async::test(feature = "__sync", async(feature = "__async", tokio::test))]
+async fn test_resolve_uri() {
+    const NOT_FOUND: &str = r#"{"error": {"status": 404, "message": "Not found"}}"#;
+    const USER: &str = r#"{"display_name": null, "external_urls": {}, "href": "https://api.spotify.com/v1/users/wizzler", "id": "wizzler", "images": []}"#;
+    let cases = [
+        ("track", "4iV5W9uYEdYUVa79Axb7Rh"),
+        ("album", "0sNOF9WDwhWunNAHPD3Baj"),
+        ("artist", "0OdUWJ0sBjDrqHygGUXeCF"),
+        ("playlist", "37i9dQZF1DXcBWIGoYBM5M"),
+        ("show", "38bS44xjbVVZ3No3ByF1dJ"),
+        ("episode", "512ojhOuo1ktJprKbVcKyQ"),
+        ("audiobook", "7iHfbu1YPACw6oZPAFJtqe"),
+        ("user", "wizzler"),
+    ];
+    // Each object is requested once with its URI and once with its URL. Only
+    // the user is actually found, the rest of the requests are only checked
+    let responses = cases
+        .iter()
+        .flat_map(|(tpe, _)| {
+            let response = if *tpe == "user" {
+                (200, USER)
+            } else {
+                (404, NOT_FOUND)
+            };
+            [response, response]
+        })
+        .collect();
+    let (url, server) = mock_server(responses);
+    let tok = Token {
+        access_token: "test-access_token".to_owned(),
+        expires_at: Some(Utc::now() + Duration::seconds(3600)),
+        ..Default::default()
+    };
+    let mut spotify = ClientCredsSpotify::from_token(tok);
+    spotify.config = Config {
+        prefix: url,
+        ..Default::default()
+    };
+
+    for (tpe, id) in cases {
+        for uri in [
+            format!("spotify:{}:{}", tpe, id),
+            format!("https://open.spotify.com/{}/{}", tpe, id),
+        ] {
+            let result = spotify.resolve_uri(&uri, None).await;
+            if tpe == "user" {
+                assert!(matches!(result, Ok(ResolvedItem::User(user)) if user.id.id() == id));
+            } else {
+                assert!(matches!(result, Err(ClientError::Api(err)) if err.status == 404));
+            }
+        }
+    }
+
+    // Each object is fetched with the endpoint for its type
+    let requests = server.join().unwrap();
+    assert_eq!(requests.len(), cases.len() * 2);
+    for (requests, (tpe, id)) in requests.chunks(2).zip(cases) {
+        let path = format!("GET /{}s/{}", tpe, id);
+        assert!(requests.iter().all(|request| request.starts_with(&path)));
+    }
+}
+
+#[maybe_async::test(feature = "__sync", async(feature = "__async", tokio::test))]
+async fn test_market_query() {
+    const NOT_FOUND: &str = r#"{"error": {"status": 404, "message": "Not found"}}"#;
+    let (url, server) = mock_server(vec![(404, NOT_FOUND); 8]);
+    let tok = Token {
+        access_token: "test-access_token".to_owned(),
+        expires_at: Some(Utc::now() + Duration::seconds(3600)),
+        ..Default::default()
+    };
+    let mut spotify = ClientCredsSpotify::from_token(tok);
+    spotify.config = Config {
+        prefix: url,
+        ..Default::default()
+    };
+
+    // Only the requests are checked, so the responses are ignored
+    let track_id = TrackId::from_id("4iV5W9uYEdYUVa79Axb7Rh").unwrap();
+    let album_id = AlbumId::from_id("0sNOF9WDwhWunNAHPD3Baj").unwrap();
+    let markets = [Market::FromToken, Market::Country(Country::UnitedStates)];
+    for market in markets {
+        let _ = spotify.track(track_id.as_ref(), Some(market)).await;
+        let _ = spotify.tracks([track_id.as_ref()], Some(market)).await;
+        let _ = spotify
+            .search("test", SearchType::Track, Some(market), None, None, None)
+            .await;
+        let _ = spotify.album(album_id.as_ref(), Some(market)).await;
+    }
+
+    let requests = server.join().unwrap();
+    assert_eq!(requests.len(), 8);
+    for (requests, expected) in requests.chunks(4).zip(["market=from_token", "market=US"]) {
+        for (request, endpoint) in requests
+            .iter()
+            .zip(["tracks/", "tracks", "search", "albums/"])
+        {
+            let request_line = request.lines().next().unwrap();
+            assert!(request_line.starts_with(&format!("GET /{}", endpoint)));
+            assert!(request_line.contains(expected), "{}", request_line);
+        }
+    }
+}
+
 #[test]
 fn test_token_is_expired() {
     let expires_in = Duration::seconds(20);
@@ -119,6 +653,7 @@ fn test_token_is_expired() {
         expires_in,
         expires_at: Some(Utc::now() + expires_in),
         refresh_token: Some("...".to_owned()),
+        ..Default::default()
     };
     assert!(!tok.is_expired());
 
@@ -129,10 +664,34 @@ fn test_token_is_expired() {
         expires_in,
         expires_at: Some(Utc::now() + expires_in),
         refresh_token: Some("...".to_owned()),
+        ..Default::default()
     };
     assert!(tok.is_expired());
 }
 
+#[test]
+fn test_token_is_expired_with_skew() {
+    let expires_in = Duration::seconds(60);
+    let tok = Token {
+        scopes: scopes!("playlist-read-private"),
+        access_token: "test-access_token".to_owned(),
+        expires_in,
+        expires_at: Some(Utc::now() + expires_in),
+        refresh_token: Some("...".to_owned()),
+        ..Default::default()
+    };
+    assert!(!tok.is_expired_with_skew(Duration::zero()));
+    assert!(!tok.is_expired_with_skew(Duration::seconds(30)));
+    assert!(tok.is_expired_with_skew(Duration::seconds(90)));
+
+    // A token without an expiration date is always considered expired
+    let tok = Token {
+        expires_at: None,
+        ..tok
+    };
+    assert!(tok.is_expired_with_skew(Duration::zero()));
+}
+
 #[test]
 fn test_parse_response_code() {
     // A random state is generated by default
@@ -168,4 +727,15 @@ fn test_parse_response_code() {
     );
     let code = spotify.parse_response_code(&url);
     assert_eq!(code, Some("AQD0yXvFEOvw".to_string()));
+
+    // The reason is reported when the code can't be obtained
+    let url = "http://localhost:8888/callback?code=AQD0yXvFEOvw&state=abc";
+    let err = spotify.try_parse_response_code(url).unwrap_err();
+    assert!(matches!(err, ClientError::InvalidState));
+    let url = format!(
+        "http://localhost:8888/callback?state={}",
+        spotify.oauth.state
+    );
+    let err = spotify.try_parse_response_code(&url).unwrap_err();
+    assert!(matches!(err, ClientError::InvalidInput(_)));
 }
@@ -18,9 +18,9 @@
 use rspotify::{
     clients::pagination::Paginator,
     model::{
-        AlbumId, ArtistId, Country, CurrentPlaybackContext, Device, EpisodeId, FullPlaylist,
-        ItemPositions, Market, Offset, PlaylistId, RecommendationsAttribute, RepeatState,
-        SearchType, ShowId, TimeLimits, TimeRange, TrackId, UserId,
+        AlbumId, ArtistId, AudiobookId, Country, CurrentPlaybackContext, Device, EpisodeId,
+        FullPlaylist, ItemPositions, Market, Offset, PlaylistId, RecommendationsAttribute,
+        RepeatState, SearchType, ShowId, TimeLimits, TimeRange, TrackId, UserId,
     },
     prelude::*,
     scopes, AuthCodeSpotify, ClientResult, Credentials, OAuth, Token,
@@ -165,7 +165,7 @@ async fn test_current_playing() {
 async fn test_current_user_followed_artists() {
     oauth_client()
         .await
-        .current_user_followed_artists(None, Some(10))
+        .current_user_followed_artists_manual(None, Some(10))
         .await
         .unwrap();
 }
@@ -248,6 +248,19 @@ async fn test_current_user_saved_tracks_add() {
     // Every track should be saved
     assert!(contains.into_iter().all(|x| x));
 
+    // Duplicated IDs collapse into a single entry
+    let contains = client
+        .current_user_saved_tracks_contains_map(
+            tracks_ids
+                .iter()
+                .chain(&tracks_ids[..1])
+                .map(TrackId::as_ref),
+        )
+        .await
+        .unwrap();
+    assert_eq!(contains.len(), tracks_ids.len());
+    assert!(tracks_ids.iter().all(|track| contains[track]));
+
     let all = fetch_all(client.current_user_saved_tracks(None)).await;
     let all = all
         .into_iter()
@@ -519,6 +532,28 @@ async fn test_search_show() {
         .unwrap();
 }
 
+#[maybe_async::test(feature = "__sync", async(feature = "__async", tokio::test))]
+#[ignore]
+async fn test_search_multi() {
+    let query = "tania bowra";
+    let result = oauth_client()
+        .await
+        .search_multi(
+            query,
+            [SearchType::Artist, SearchType::Album, SearchType::Track],
+            None,
+            None,
+            Some(10),
+            Some(0),
+        )
+        .await
+        .unwrap();
+    assert!(result.artists.is_some());
+    assert!(result.albums.is_some());
+    assert!(result.tracks.is_some());
+    assert!(result.playlists.is_none());
+}
+
 #[maybe_async::test(feature = "__sync", async(feature = "__async", tokio::test))]
 #[ignore]
 async fn test_seek_track() {
@@ -585,7 +620,21 @@ async fn test_user_follow_users() {
         .user_follow_users(users.iter().map(UserId::as_ref))
         .await
         .unwrap();
-    client.user_unfollow_users(users).await.unwrap();
+    let following = client
+        .user_is_following_users(users.iter().map(UserId::as_ref))
+        .await
+        .unwrap();
+    assert_eq!(following, [true, true]);
+
+    client
+        .user_unfollow_users(users.iter().map(UserId::as_ref))
+        .await
+        .unwrap();
+    let following = client
+        .user_is_following_users(users.iter().map(UserId::as_ref))
+        .await
+        .unwrap();
+    assert_eq!(following, [false, false]);
 }
 
 #[maybe_async::test(feature = "__sync", async(feature = "__async", tokio::test))]
@@ -749,16 +798,28 @@ async fn check_playlist_follow(client: &AuthCodeSpotify, playlist: &FullPlaylist
 
     // It's a new playlist, so it shouldn't have any followers
     let following = client
-        .playlist_check_follow(playlist.id.as_ref(), &user_ids)
+        .playlist_check_follow(playlist.id.as_ref(), user_ids)
         .await
         .unwrap();
     assert_eq!(following, vec![false, false]);
 
+    // The creator of the playlist follows it
+    let follows = client
+        .me_follows_playlist(playlist.id.as_ref())
+        .await
+        .unwrap();
+    assert!(follows);
+
     // Finally unfollowing the playlist in order to clean it up
     client
         .playlist_unfollow(playlist.id.as_ref())
         .await
         .unwrap();
+    let follows = client
+        .me_follows_playlist(playlist.id.as_ref())
+        .await
+        .unwrap();
+    assert!(!follows);
 }
 
 #[maybe_async::test(feature = "__sync", async(feature = "__async", tokio::test))]
@@ -835,3 +896,17 @@ async fn test_get_several_episodes() {
         .await
         .unwrap();
 }
+
+#[maybe_async::test(feature = "__sync", async(feature = "__async", tokio::test))]
+#[ignore]
+async fn test_get_audiobooks() {
+    let audiobooks = [
+        AudiobookId::from_id("7iHfbu1YPACw6oZPAFJtqe").unwrap(),
+        AudiobookId::from_id("18yVqkdbdRvS24c0Ilj2ci").unwrap(),
+    ];
+    oauth_client()
+        .await
+        .get_audiobooks(audiobooks, None)
+        .await
+        .unwrap();
+}